@@ -35,6 +35,15 @@ impl pierro::DockingTab for DockingTab {
         }
     }
 
+    fn context_menu(&mut self, ui: &mut pierro::UI, _context: &mut ()) -> bool {
+        pierro::h_line(ui);
+        let reset = pierro::menu_button(ui, "Reset").mouse_clicked();
+        if reset {
+            self.n = 0;
+        }
+        reset
+    }
+
 }
 
 struct DockingApp {
@@ -55,6 +64,16 @@ impl pierro::App for DockingApp {
                 pierro::menu_button(ui, "Stuff");
                 pierro::menu_button(ui, "Here");
             });
+            pierro::menu_bar_item(ui, "Window", |ui| {
+                if pierro::menu_button(ui, "Inspector").mouse_clicked() {
+                    if let Some(inspector) = self.docking_state.find_tab(|tab| tab.text == "Inspector") {
+                        self.docking_state.focus_tab(inspector);
+                    } else {
+                        let root = self.docking_state.root();
+                        self.docking_state.add_tab_split(DockingTab { text: "Inspector", n: 0 }, root, pierro::Axis::X, true);
+                    }
+                }
+            });
         });
         self.docking_state.render(ui, &mut ());
     }
@@ -64,7 +64,9 @@ impl Basic {
 struct Layout {
     axis: pierro::Axis,
     justify: pierro::Justify,
-    align: pierro::Align
+    align: pierro::Align,
+    wrap: bool,
+    badge: bool
 }
 
 impl Layout {
@@ -73,7 +75,9 @@ impl Layout {
         Self {
             axis: pierro::Axis::X,
             justify: pierro::Justify::Center,
-            align: pierro::Align::Center
+            align: pierro::Align::Center,
+            wrap: false,
+            badge: false
         }
     }
 
@@ -104,6 +108,9 @@ impl Layout {
                 pierro::Justify::Min => "Min",
                 pierro::Justify::Center => "Center",
                 pierro::Justify::Max => "Max",
+                pierro::Justify::SpaceBetween => "Space Between",
+                pierro::Justify::SpaceAround => "Space Around",
+                pierro::Justify::SpaceEvenly => "Space Evenly",
             }, |ui| {
                 if pierro::menu_button(ui, "Min").mouse_clicked() {
                     self.justify = pierro::Justify::Min;
@@ -114,6 +121,15 @@ impl Layout {
                 if pierro::menu_button(ui, "Max").mouse_clicked() {
                     self.justify = pierro::Justify::Max;
                 }
+                if pierro::menu_button(ui, "Space Between").mouse_clicked() {
+                    self.justify = pierro::Justify::SpaceBetween;
+                }
+                if pierro::menu_button(ui, "Space Around").mouse_clicked() {
+                    self.justify = pierro::Justify::SpaceAround;
+                }
+                if pierro::menu_button(ui, "Space Evenly").mouse_clicked() {
+                    self.justify = pierro::Justify::SpaceEvenly;
+                }
             });
             pierro::v_spacing(ui, 5.0);
 
@@ -132,17 +148,40 @@ impl Layout {
                     self.align = pierro::Align::Max;
                 }
             });
+            pierro::v_spacing(ui, 5.0);
+
+            pierro::checkbox_labeled(ui, "Wrap", &mut self.wrap);
+            pierro::v_spacing(ui, 5.0);
+
+            pierro::checkbox_labeled(ui, "Badge", &mut self.badge);
         });
 
         pierro::h_divider(ui);
+        let mut layout = pierro::Layout::new(self.axis).with_justify(self.justify).with_align(self.align);
+        if self.wrap {
+            layout = layout.with_wrap().with_gap(10.0, 10.0);
+        }
         pierro::container(ui,
             pierro::Size::fr(1.0),
             pierro::Size::fr(1.0),
-            pierro::Layout::new(self.axis).with_justify(self.justify).with_align(self.align),
+            layout,
             |ui| {
                 self.node(ui, pierro::Color::RED);   
                 self.node(ui, pierro::Color::GREEN);   
                 self.node(ui, pierro::Color::BLUE);
+                if self.wrap {
+                    self.node(ui, pierro::Color::YELLOW);
+                    self.node(ui, pierro::Color::PURPLE);
+                    self.node(ui, pierro::Color::WHITE);
+                }
+                if self.badge {
+                    ui.node(
+                        pierro::UINodeParams::new(pierro::Size::px(30.0), pierro::Size::px(30.0))
+                            .with_anchor(pierro::Anchor::top_right().with_offset(pierro::vec2(-10.0, 10.0)))
+                            .with_fill(pierro::Color::WHITE)
+                            .with_rounding(15.0)
+                    );
+                }
             });
     }
 
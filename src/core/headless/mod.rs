@@ -0,0 +1,161 @@
+
+use crate::{text::TextResources, App, DisplayList, Id, Input, Memory, Painter, RawInput, Rect, SoftwareRenderer, TextRenderCache, UIQuery, Vec2, UI};
+
+use super::UITree;
+
+mod simulate;
+mod replay;
+
+/// Runs the UI without a window or a GPU.
+/// Useful for testing widgets and running the UI on machines without a display.
+pub struct Headless {
+    raw_input: RawInput,
+    input: Input,
+    memory: Memory,
+    text_resources: TextResources,
+    text_render_cache: TextRenderCache,
+
+    /// The tree produced by the last frame
+    tree: UITree,
+    /// Everything painted in the last frame
+    display_list: DisplayList,
+    renderer: SoftwareRenderer,
+
+    window_size: Vec2,
+    scale_factor: f32,
+    delta_time: f32
+}
+
+impl Headless {
+
+    /// Create a headless UI with a window of a given logical size
+    pub fn new(window_size: Vec2) -> Self {
+        // Headless UIs are mostly used for tests, which query the nodes of every frame
+        let mut memory = Memory::new();
+        memory.take_queries = true;
+        Self {
+            raw_input: RawInput::new(),
+            input: Input::new(),
+            memory,
+            text_resources: TextResources::new(),
+            text_render_cache: TextRenderCache::new(),
+            tree: UITree::new(),
+            display_list: DisplayList::new(window_size, 1.0),
+            renderer: SoftwareRenderer::new(),
+            window_size,
+            scale_factor: 1.0,
+            delta_time: 1.0 / 60.0
+        }
+    }
+
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Set the amount of time that passes between frames
+    pub fn with_delta_time(mut self, delta_time: f32) -> Self {
+        self.delta_time = delta_time;
+        self
+    }
+
+    pub fn window_size(&self) -> Vec2 {
+        self.window_size
+    }
+
+    pub fn set_window_size(&mut self, window_size: Vec2) {
+        self.window_size = window_size;
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    pub fn delta_time(&self) -> f32 {
+        self.delta_time
+    }
+
+    /// The raw input that will be given to the UI on the next frame.
+    /// Mouse positions are in physical pixels, as they would be when coming from the window.
+    pub fn raw_input(&mut self) -> &mut RawInput {
+        &mut self.raw_input
+    }
+
+    /// The input given to the UI on the last frame
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    pub fn memory(&mut self) -> &mut Memory {
+        &mut self.memory
+    }
+
+    /// Build, lay out and paint a frame of the UI
+    pub fn tick<R, F: FnOnce(&mut UI) -> R>(&mut self, body: F) -> R {
+        self.raw_input.delta_time = self.delta_time;
+        self.input.update(&mut self.raw_input, self.scale_factor);
+        self.input.distribute(&mut self.memory, Id::ROOT);
+        self.input.release_dnd_payload(&mut self.memory);
+
+        let mut ui = UI::new(
+            &self.input,
+            &mut self.memory,
+            &mut self.text_resources,
+            None,
+            None,
+            None,
+            self.window_size
+        );
+        let result = body(&mut ui);
+        self.tree = ui.finish().tree;
+
+        let mut painter = Painter::new(&mut self.text_resources, &mut self.text_render_cache, self.window_size, self.scale_factor);
+        self.tree.paint(&mut painter);
+        self.display_list = painter.finish();
+
+        result
+    }
+
+    /// Build, lay out and paint a frame of an app
+    pub fn tick_app<T: App>(&mut self, app: &mut T) {
+        self.tick(|ui| app.tick(ui));
+    }
+
+    /// The nodes in the last frame, for finding widgets and inspecting them
+    pub fn query(&self) -> &UIQuery {
+        self.memory.queries.get(&Id::ROOT).unwrap_or(UIQuery::empty())
+    }
+
+    /// Everything painted in the last frame
+    pub fn display_list(&self) -> &DisplayList {
+        &self.display_list
+    }
+
+    /// The renderer used to produce images of the UI
+    pub fn renderer(&mut self) -> &mut SoftwareRenderer {
+        &mut self.renderer
+    }
+
+    /// Render the last frame into an image, in physical pixels
+    pub fn render_image(&self) -> image::RgbaImage {
+        self.renderer.render(&self.display_list, &self.text_resources.atlas)
+    }
+
+    /// The rectangles of all the nodes in the last frame, with transformations applied.
+    /// Nodes are listed in the order they were created, layer by layer.
+    pub fn node_rects(&self) -> Vec<(Id, Rect)> {
+        self.tree.nodes.iter().map(|node| (node.id, node.transform * node.rect)).collect()
+    }
+
+    /// The rectangle of a node in the last frame, with transformations applied
+    pub fn node_rect(&self, id: Id) -> Option<Rect> {
+        self.tree.nodes.iter()
+            .find(|node| node.id == id)
+            .map(|node| node.transform * node.rect)
+    }
+
+}
@@ -0,0 +1,29 @@
+
+use crate::{App, InputRecording, RecordedFrame, UI};
+
+use super::Headless;
+
+impl Headless {
+
+    /// Run a single recorded frame of input, using the window size, scale factor and delta time it was recorded with
+    pub fn replay_frame<R, F: FnOnce(&mut UI) -> R>(&mut self, frame: &RecordedFrame, body: F) -> R {
+        self.window_size = frame.window_size;
+        self.scale_factor = frame.scale_factor;
+        self.delta_time = frame.raw_input.delta_time;
+        self.raw_input = frame.raw_input.clone();
+        self.tick(body)
+    }
+
+    /// Replay every frame of a recording
+    pub fn replay<F: FnMut(&mut UI)>(&mut self, recording: &InputRecording, mut body: F) {
+        for frame in &recording.frames {
+            self.replay_frame(frame, &mut body);
+        }
+    }
+
+    /// Replay every frame of a recording on an app
+    pub fn replay_app<T: App>(&mut self, recording: &InputRecording, app: &mut T) {
+        self.replay(recording, |ui| app.tick(ui));
+    }
+
+}
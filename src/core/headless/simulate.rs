@@ -0,0 +1,201 @@
+
+use crate::{Id, Key, KeyCode, KeyEvent, LogicalKey, PenInput, Vec2, UI};
+
+use super::Headless;
+
+/// Scripted input, for driving the UI in tests.
+/// Methods that take a `body` run frames of the UI so that the input is seen by widgets.
+/// All positions are in logical pixels.
+impl Headless {
+
+    /// Move the mouse to a position
+    pub fn move_mouse(&mut self, pos: Vec2) {
+        self.raw_input.mouse_pos = Some(pos * self.scale_factor);
+    }
+
+    /// Move the mouse to the center of a node from the last frame.
+    /// Returns false if the node does not exist.
+    pub fn move_mouse_to_node(&mut self, id: Id) -> bool {
+        let Some(rect) = self.node_rect(id) else { return false; };
+        self.move_mouse(rect.center());
+        true
+    }
+
+    /// Move the mouse out of the window
+    pub fn mouse_leave(&mut self) {
+        self.raw_input.mouse_pos = None;
+    }
+
+    /// The current position of the simulated mouse
+    pub fn mouse_pos(&self) -> Option<Vec2> {
+        self.raw_input.mouse_pos.map(|pos| pos / self.scale_factor)
+    }
+
+    pub fn press_l_mouse(&mut self) {
+        self.raw_input.l_mouse_down = true;
+    }
+
+    pub fn release_l_mouse(&mut self) {
+        self.raw_input.l_mouse_down = false;
+    }
+
+    pub fn press_r_mouse(&mut self) {
+        self.raw_input.r_mouse_down = true;
+    }
+
+    pub fn release_r_mouse(&mut self) {
+        self.raw_input.r_mouse_down = false;
+    }
+
+    pub fn press_m_mouse(&mut self) {
+        self.raw_input.m_mouse_down = true;
+    }
+
+    pub fn release_m_mouse(&mut self) {
+        self.raw_input.m_mouse_down = false;
+    }
+
+    pub fn press_back_mouse(&mut self) {
+        self.raw_input.back_mouse_down = true;
+    }
+
+    pub fn release_back_mouse(&mut self) {
+        self.raw_input.back_mouse_down = false;
+    }
+
+    pub fn press_forward_mouse(&mut self) {
+        self.raw_input.forward_mouse_down = true;
+    }
+
+    pub fn release_forward_mouse(&mut self) {
+        self.raw_input.forward_mouse_down = false;
+    }
+
+    /// Touch the screen with a pen at a position with a pressure from 0 to 1, or lift it with `None`
+    pub fn set_pen(&mut self, pen: Option<(Vec2, f32)>) {
+        self.raw_input.pen = pen.map(|(pos, pressure)| PenInput {
+            position: pos * self.scale_factor,
+            pressure,
+            altitude: None
+        });
+    }
+
+    /// Scroll the mouse wheel by a given amount
+    pub fn scroll(&mut self, delta: Vec2) {
+        self.raw_input.scroll += delta * self.scale_factor;
+    }
+
+    /// Queue up a key event. Modifier keys also update the held modifiers, as if they were on the left of the keyboard.
+    fn key_event(&mut self, key: Key, physical_key: Option<KeyCode>, pressed: bool) {
+        if let Some(logical_key) = key.logical_key {
+            self.raw_input.modifiers.set_logical_key(logical_key, pressed);
+        }
+        self.raw_input.key_events.push(KeyEvent {
+            key,
+            physical_key,
+            pressed,
+            modifiers: self.raw_input.modifiers
+        });
+    }
+
+    pub fn press_key(&mut self, key: Key) {
+        self.key_event(key, None, true);
+    }
+
+    pub fn release_key(&mut self, key: Key) {
+        self.key_event(key, None, false);
+    }
+
+    /// Press a key at a physical location on the keyboard, which means `key` in the current layout
+    pub fn press_physical_key(&mut self, physical_key: KeyCode, key: Key) {
+        self.key_event(key, Some(physical_key), true);
+    }
+
+    /// Release a key at a physical location on the keyboard, which means `key` in the current layout
+    pub fn release_physical_key(&mut self, physical_key: KeyCode, key: Key) {
+        self.key_event(key, Some(physical_key), false);
+    }
+
+    /// Press a key with a logical meaning, like `Enter` or `ArrowLeft`
+    pub fn press_logical_key(&mut self, logical_key: LogicalKey) {
+        self.press_key(Key { text: None, logical_key: Some(logical_key) });
+    }
+
+    /// Release a key with a logical meaning, like `Enter` or `ArrowLeft`
+    pub fn release_logical_key(&mut self, logical_key: LogicalKey) {
+        self.release_key(Key { text: None, logical_key: Some(logical_key) });
+    }
+
+    /// Press and release a key within a single frame
+    pub fn tap_key(&mut self, key: Key) {
+        self.press_key(key.clone());
+        self.release_key(key);
+    }
+
+    /// Queue up key presses typing out a string, as the window would report them.
+    /// The text is seen by the UI on the next frame.
+    pub fn type_text(&mut self, text: &str) {
+        for char in text.chars() {
+            let key = match char {
+                ' ' => Key { text: None, logical_key: Some(LogicalKey::Space) },
+                '\n' => Key { text: None, logical_key: Some(LogicalKey::Enter) },
+                '\t' => Key { text: None, logical_key: Some(LogicalKey::Tab) },
+                _ => Key { text: Some(char.to_string()), logical_key: None }
+            };
+            self.tap_key(key);
+        }
+    }
+
+    /// Run a number of frames of the UI
+    pub fn run_frames<F: FnMut(&mut UI)>(&mut self, frames: u32, mut body: F) {
+        for _ in 0..frames {
+            self.tick(&mut body);
+        }
+    }
+
+    /// Click the left mouse button on a node, running the frames needed for the click to register.
+    /// Returns false if the node does not exist.
+    pub fn click_node<F: FnMut(&mut UI)>(&mut self, id: Id, mut body: F) -> bool {
+        if !self.move_mouse_to_node(id) {
+            return false;
+        }
+        self.tick(&mut body);
+        self.press_l_mouse();
+        self.tick(&mut body);
+        self.release_l_mouse();
+        self.tick(&mut body);
+        true
+    }
+
+    /// Drag with the left mouse button from one position to another, moving the mouse over a number of frames
+    pub fn drag<F: FnMut(&mut UI)>(&mut self, from: Vec2, to: Vec2, steps: u32, mut body: F) {
+        let steps = steps.max(1);
+        self.move_mouse(from);
+        self.tick(&mut body);
+        self.press_l_mouse();
+        self.tick(&mut body);
+        for i in 1..=steps {
+            let t = i as f32 / steps as f32;
+            self.move_mouse(from + (to - from) * t);
+            self.tick(&mut body);
+        }
+        self.release_l_mouse();
+        self.tick(&mut body);
+    }
+
+    /// Drag a node from the last frame to a position.
+    /// Returns false if the node does not exist.
+    pub fn drag_node<F: FnMut(&mut UI)>(&mut self, id: Id, to: Vec2, steps: u32, body: F) -> bool {
+        let Some(rect) = self.node_rect(id) else { return false; };
+        self.drag(rect.center(), to, steps, body);
+        true
+    }
+
+    /// Drag a node from the last frame onto the center of another node.
+    /// Returns false if either node does not exist.
+    pub fn drag_node_to_node<F: FnMut(&mut UI)>(&mut self, from: Id, to: Id, steps: u32, body: F) -> bool {
+        let Some(to) = self.node_rect(to) else { return false; };
+        self.drag_node(from, to.center(), steps, body)
+    }
+
+}
@@ -0,0 +1,476 @@
+
+use std::time::Instant;
+
+use winit::{dpi::{LogicalPosition, LogicalSize, PhysicalSize, Position, Size}, event::{Force, Ime, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent}};
+
+use crate::{text::TextResources, vec2, ChildWindows, Color, CursorIcon, DisplayList, FrameOutput, Id, Input, InputRecorder, Key, KeyCode, KeyEvent, LayoutMemory, LogicalKey, Memory, Modifiers, Painter, PenInput, RawInput, RecordedFrame, Rect, RenderTarget, TextRenderCache, UIQuery, WgpuRenderer, WindowConfig, UI};
+
+/// What the UI wants from the window after a frame
+pub struct IntegrationOutput {
+    /// Does the UI need to be redrawn, e.g. because an animation is playing?
+    pub request_redraw: bool,
+    pub cursor: CursorIcon,
+    /// The area of the node receiving IME input, in logical pixels. `None` if no node wants IME input.
+    pub ime_rect: Option<Rect>
+}
+
+impl IntegrationOutput {
+
+    pub fn winit_cursor(&self) -> winit::window::CursorIcon {
+        pierro_to_winit_cursor(self.cursor)
+    }
+
+    /// Apply the cursor and IME requests to a window
+    pub fn apply_to_window(&self, window: &winit::window::Window) {
+        window.set_cursor(self.winit_cursor());
+        window.set_ime_allowed(self.ime_rect.is_some());
+        if let Some(rect) = self.ime_rect {
+            let logical_position = Position::Logical(LogicalPosition::new(rect.left() as f64, rect.top() as f64));
+            let logical_size = Size::Logical(LogicalSize::new(rect.width() as f64, rect.height() as f64));
+            window.set_ime_cursor_area(logical_position, logical_size);
+        }
+    }
+
+}
+
+/// Runs pierro inside an existing winit + wgpu application.
+/// Feed it window events with `handle_event`, build a frame of the UI with `frame`, then draw it with `render`.
+pub struct Integration {
+    raw_input: RawInput,
+    input: Input,
+    memory: Memory,
+    text_resources: TextResources,
+    /// Glyph layouts of the text painted in the main window last frame
+    text_render_cache: TextRenderCache,
+    renderer: WgpuRenderer,
+    clipboard: Option<arboard::Clipboard>,
+    recorder: Option<InputRecorder>,
+    /// The touch used as pen input in the main window, if a pen is touching it
+    pen_touch: Option<u64>,
+    windows: ChildWindows,
+
+    /// What was painted in the last frame, waiting to be rendered
+    display_list: Option<DisplayList>,
+    prev_frame_time: Instant
+}
+
+impl Integration {
+
+    /// Create an integration that renders to textures of a given format
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        Self {
+            raw_input: RawInput::new(),
+            input: Input::new(),
+            memory: Memory::new(),
+            text_resources: TextResources::new(),
+            text_render_cache: TextRenderCache::new(),
+            renderer: WgpuRenderer::new(device, target_format),
+            clipboard: arboard::Clipboard::new().ok(),
+            recorder: None,
+            pen_touch: None,
+            windows: ChildWindows::new(),
+            display_list: None,
+            prev_frame_time: Instant::now()
+        }
+    }
+
+    /// Set the color the target is cleared to before the UI is rendered.
+    /// If `None`, the UI is drawn on top of the existing contents of the target.
+    pub fn with_clear_color(mut self, clear_color: Option<Color>) -> Self {
+        self.renderer.clear_color = clear_color;
+        self
+    }
+
+    /// Record the input of every frame
+    pub fn with_input_recorder(mut self, recorder: InputRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// The raw input that will be given to the UI on the next frame
+    pub fn raw_input(&mut self) -> &mut RawInput {
+        &mut self.raw_input
+    }
+
+    pub fn memory(&mut self) -> &mut Memory {
+        &mut self.memory
+    }
+
+    /// The nodes of the main window in the last frame, e.g. for an inspector or for automating the UI.
+    /// Snapshots of the nodes are only taken once one is asked for, so this is empty the first time it is called.
+    pub fn query(&mut self) -> &UIQuery {
+        self.memory.query(Id::ROOT)
+    }
+
+    /// Update the input given to the UI with an event from the window
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        handle_window_event(&mut self.raw_input, &mut self.pen_touch, event);
+    }
+
+    /// Build, lay out and paint a frame of the UI.
+    /// The frame is drawn by the next call to `render`.
+    pub fn frame<F: FnOnce(&mut UI)>(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, physical_size: PhysicalSize<u32>, scale_factor: f32, body: F) -> IntegrationOutput {
+        let size = vec2(physical_size.width as f32, physical_size.height as f32) / scale_factor;
+
+        self.raw_input.delta_time = self.prev_frame_time.elapsed().as_secs_f32();
+        self.prev_frame_time = Instant::now();
+        self.windows.begin_frame(self.raw_input.delta_time);
+
+        // record input
+        if let Some(recorder) = &mut self.recorder {
+            let frame = RecordedFrame {
+                raw_input: self.raw_input.clone(),
+                window_size: size,
+                scale_factor
+            };
+            if let Err(err) = recorder.record(&frame) {
+                eprintln!("pierro: could not record input: {}", err);
+                self.recorder = None;
+            }
+        }
+
+        // distribute input
+        self.input.update(&mut self.raw_input, scale_factor);
+        self.input.distribute(&mut self.memory, Id::ROOT);
+        self.input.release_dnd_payload(&mut self.memory);
+
+        // ui generation and layout
+        let mut ui = UI::new(
+            &self.input,
+            &mut self.memory,
+            &mut self.text_resources,
+            self.clipboard.as_mut(),
+            Some(device),
+            Some(queue),
+            size
+        ).with_child_windows(&mut self.windows);
+        body(&mut ui);
+        let FrameOutput { mut tree, request_redraw, cursor, request_ime } = ui.finish();
+
+        self.windows.close_unused();
+        let windows = &self.windows;
+        self.memory.layer_ids.retain(|root_id, _| *root_id == Id::ROOT || windows.windows.contains_key(root_id));
+        self.memory.queries.retain(|root_id, _| *root_id == Id::ROOT || windows.windows.contains_key(root_id));
+
+        let ime_rect = request_ime.map(|ime_node| {
+            let id = tree.get(ime_node).id;
+            self.memory.get::<LayoutMemory>(id).screen_rect
+        });
+
+        // ui painting
+        let mut painter = Painter::new(&mut self.text_resources, &mut self.text_render_cache, size, scale_factor);
+        tree.paint(&mut painter);
+        self.display_list = Some(painter.finish());
+
+        IntegrationOutput {
+            request_redraw,
+            cursor,
+            ime_rect
+        }
+    }
+
+    /// Draw the last frame into a texture view.
+    /// The view must have the format given in `Integration::new`. `target_size` is the size of the texture the view is of, in physical pixels.
+    pub fn render(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, target_size: PhysicalSize<u32>) {
+        let Some(display_list) = self.display_list.take() else { return; };
+        self.renderer.render(device, queue, encoder, RenderTarget { view, size: (target_size.width, target_size.height) }, &mut self.text_resources.atlas, display_list);
+    }
+
+    /// The ids of the child windows opened during the last frame
+    pub(crate) fn child_window_ids(&self) -> Vec<Id> {
+        self.windows.windows.keys().copied().collect()
+    }
+
+    /// Take the configs of the child windows that were opened for the first time during the last frame
+    pub(crate) fn take_new_child_windows(&mut self) -> Vec<(Id, WindowConfig)> {
+        self.windows.windows.iter_mut()
+            .filter_map(|(id, window)| Some((*id, window.config.take()?)))
+            .collect()
+    }
+
+    /// Tell the UI that a child window could not be created, so that it stops waiting for it
+    pub(crate) fn child_window_failed(&mut self, id: Id) {
+        if let Some(window) = self.windows.windows.get_mut(&id) {
+            window.failed = true;
+        }
+    }
+
+    /// Tell the UI the size of a child window
+    pub(crate) fn set_child_window_size(&mut self, id: Id, physical_size: PhysicalSize<u32>, scale_factor: f32) {
+        if let Some(window) = self.windows.windows.get_mut(&id) {
+            window.physical_size = Some(vec2(physical_size.width as f32, physical_size.height as f32));
+            window.scale_factor = scale_factor;
+        }
+    }
+
+    /// Update the input given to a child window with an event from its window
+    pub(crate) fn handle_child_window_event(&mut self, id: Id, event: &WindowEvent) {
+        let Some(window) = self.windows.windows.get_mut(&id) else { return; };
+        if let WindowEvent::CloseRequested = event {
+            window.close_requested = true;
+        }
+        handle_window_event(&mut window.raw_input, &mut window.pen_touch, event);
+    }
+
+    /// Draw the last frame of a child window into a texture view, returning what the window wants from the OS
+    pub(crate) fn render_child_window(&mut self, id: Id, device: &wgpu::Device, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, target_size: PhysicalSize<u32>) -> Option<IntegrationOutput> {
+        let window = self.windows.windows.get_mut(&id)?;
+        let display_list = window.display_list.take()?;
+        self.renderer.render(device, queue, encoder, RenderTarget { view, size: (target_size.width, target_size.height) }, &mut self.text_resources.atlas, display_list);
+        window.output.take()
+    }
+
+}
+
+/// Update raw input with an event from a window. `pen_touch` keeps track of the touch used as pen input.
+fn handle_window_event(raw_input: &mut RawInput, pen_touch: &mut Option<u64>, event: &WindowEvent) {
+    match event {
+        WindowEvent::MouseInput { device_id: _, state, button } => {
+            match button {
+                MouseButton::Left => {
+                    raw_input.l_mouse_down = state.is_pressed();
+                },
+                MouseButton::Right => {
+                    raw_input.r_mouse_down = state.is_pressed();
+                },
+                MouseButton::Middle => {
+                    raw_input.m_mouse_down = state.is_pressed();
+                },
+                MouseButton::Back => {
+                    raw_input.back_mouse_down = state.is_pressed();
+                },
+                MouseButton::Forward => {
+                    raw_input.forward_mouse_down = state.is_pressed();
+                },
+                _ => {}
+            }
+        },
+        WindowEvent::Touch(touch) => {
+            let is_pen_touch = *pen_touch == Some(touch.id);
+            match touch.phase {
+                TouchPhase::Started | TouchPhase::Moved => {
+                    // Only pens report calibrated force, other touches are left to the platform's mouse emulation.
+                    // Other pens touching the screen at the same time are ignored.
+                    if let Some(force @ Force::Calibrated { altitude_angle, .. }) = touch.force {
+                        if pen_touch.is_none() || is_pen_touch {
+                            *pen_touch = Some(touch.id);
+                            raw_input.pen = Some(PenInput {
+                                position: vec2(touch.location.x as f32, touch.location.y as f32),
+                                pressure: force.normalized() as f32,
+                                altitude: altitude_angle.map(|angle| angle as f32)
+                            });
+                        }
+                    }
+                },
+                TouchPhase::Ended | TouchPhase::Cancelled => if is_pen_touch {
+                    *pen_touch = None;
+                    raw_input.pen = None;
+                }
+            }
+        },
+        WindowEvent::CursorLeft { device_id: _ } => {
+            raw_input.mouse_pos = None;
+        },
+        WindowEvent::CursorMoved { device_id: _, position } => {
+            raw_input.mouse_pos = Some(vec2(position.x as f32, position.y as f32))
+        },
+        WindowEvent::MouseWheel { device_id: _, delta, phase: _ } => {
+            match delta {
+                MouseScrollDelta::LineDelta(x, y) => {
+                    raw_input.scroll += vec2(*x, *y) * 5.0;
+                },
+                MouseScrollDelta::PixelDelta(physical_position) => {
+                    raw_input.scroll += vec2(physical_position.x as f32, physical_position.y as f32);
+                },
+            }
+        },
+
+        WindowEvent::KeyboardInput { device_id: _, event, is_synthetic: _ } => {
+            let key = winit_to_pierro_key(event.logical_key.clone());
+            let physical_key = winit_to_pierro_key_code(event.physical_key);
+            if key.is_some() || physical_key.is_some() {
+                raw_input.key_events.push(KeyEvent {
+                    key: key.unwrap_or(Key { text: None, logical_key: None }),
+                    physical_key,
+                    pressed: event.state.is_pressed(),
+                    modifiers: raw_input.modifiers
+                });
+            }
+        },
+
+        WindowEvent::ModifiersChanged(modifiers) => {
+            raw_input.modifiers = winit_to_pierro_modifiers(modifiers);
+        },
+
+        WindowEvent::Ime(Ime::Preedit(preedit, _)) => {
+            raw_input.ime_preedit = preedit.clone();
+        },
+        WindowEvent::Ime(Ime::Commit(text)) => {
+            raw_input.ime_commit = Some(text.clone());
+        },
+
+        _ => {}
+    }
+}
+
+fn winit_to_pierro_key(key: winit::keyboard::Key) -> Option<Key> {
+
+    macro_rules! handle_logical_key {
+        ($key: ident) => {
+            if key == winit::keyboard::Key::Named(winit::keyboard::NamedKey::$key) {
+                return Some(Key {
+                    text: None,
+                    logical_key: Some(LogicalKey::$key)
+                });
+            }   
+        };
+    }
+
+    handle_logical_key!(Alt);
+    handle_logical_key!(CapsLock);
+    handle_logical_key!(Control);
+    handle_logical_key!(Fn);
+    handle_logical_key!(Shift);
+    if key == winit::keyboard::Key::Named(winit::keyboard::NamedKey::Super) {
+        return Some(Key {
+            text: None,
+            logical_key: Some(LogicalKey::Command)
+        });
+    }
+    handle_logical_key!(Enter);
+    handle_logical_key!(Tab);
+    handle_logical_key!(Space);
+    handle_logical_key!(ArrowDown);
+    handle_logical_key!(ArrowLeft);
+    handle_logical_key!(ArrowRight);
+    handle_logical_key!(ArrowUp);
+    handle_logical_key!(Backspace);
+    handle_logical_key!(Delete);
+    handle_logical_key!(Escape);
+    handle_logical_key!(Home);
+    handle_logical_key!(End);
+    handle_logical_key!(F1);
+    handle_logical_key!(F2);
+    handle_logical_key!(F3);
+    handle_logical_key!(F4);
+    handle_logical_key!(F5);
+    handle_logical_key!(F6);
+    handle_logical_key!(F7);
+    handle_logical_key!(F8);
+    handle_logical_key!(F9);
+    handle_logical_key!(F10);
+    handle_logical_key!(F11);
+    handle_logical_key!(F12);
+    handle_logical_key!(PageUp);
+    handle_logical_key!(PageDown);
+    handle_logical_key!(Insert);
+    handle_logical_key!(NumLock);
+    handle_logical_key!(MediaPlayPause);
+    handle_logical_key!(MediaStop);
+    handle_logical_key!(MediaTrackNext);
+    handle_logical_key!(MediaTrackPrevious);
+    handle_logical_key!(AudioVolumeUp);
+    handle_logical_key!(AudioVolumeDown);
+    handle_logical_key!(AudioVolumeMute);
+
+    if let winit::keyboard::Key::Character(text) = key {
+        return Some(Key {
+            text: Some(text.into()),
+            logical_key: None,
+        });
+    }
+
+    None
+}
+
+fn winit_to_pierro_key_code(key: winit::keyboard::PhysicalKey) -> Option<KeyCode> {
+    let winit::keyboard::PhysicalKey::Code(code) = key else { return None; };
+
+    macro_rules! key_codes {
+        ($($key: ident),*) => {
+            match code {
+                $(winit::keyboard::KeyCode::$key => Some(KeyCode::$key),)*
+                _ => None
+            }
+        };
+    }
+
+    key_codes!(
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM,
+        KeyN, KeyO, KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+        Minus, Equal, BracketLeft, BracketRight, Backslash, Semicolon, Quote, Backquote, Comma, Period, Slash,
+        AltLeft, AltRight, ControlLeft, ControlRight, ShiftLeft, ShiftRight, SuperLeft, SuperRight, CapsLock, Fn,
+        Enter, Tab, Space, Backspace, Delete, Escape, Insert, Home, End, PageUp, PageDown,
+        ArrowDown, ArrowLeft, ArrowRight, ArrowUp,
+        NumLock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+        NumpadAdd, NumpadSubtract, NumpadMultiply, NumpadDivide, NumpadDecimal, NumpadEnter,
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        MediaPlayPause, MediaStop, MediaTrackNext, MediaTrackPrevious, AudioVolumeUp, AudioVolumeDown, AudioVolumeMute
+    )
+}
+
+fn winit_to_pierro_modifiers(modifiers: &winit::event::Modifiers) -> Modifiers {
+    use winit::keyboard::ModifiersKeyState;
+
+    let state = modifiers.state();
+    // Platforms that don't report which side a modifier is on get it put on the left
+    let sides = |held: bool, left: ModifiersKeyState, right: ModifiersKeyState| {
+        let right = right == ModifiersKeyState::Pressed;
+        let left = left == ModifiersKeyState::Pressed || (held && !right);
+        (left, right)
+    };
+    let (left_ctrl, right_ctrl) = sides(state.control_key(), modifiers.lcontrol_state(), modifiers.rcontrol_state());
+    let (left_shift, right_shift) = sides(state.shift_key(), modifiers.lshift_state(), modifiers.rshift_state());
+    let (left_alt, right_alt) = sides(state.alt_key(), modifiers.lalt_state(), modifiers.ralt_state());
+    let (left_logo, right_logo) = sides(state.super_key(), modifiers.lsuper_state(), modifiers.rsuper_state());
+
+    Modifiers {
+        left_ctrl,
+        right_ctrl,
+        left_shift,
+        right_shift,
+        left_alt,
+        right_alt,
+        left_logo,
+        right_logo
+    }
+}
+
+fn pierro_to_winit_cursor(cursor: CursorIcon) -> winit::window::CursorIcon {
+    match cursor {
+        CursorIcon::Default => winit::window::CursorIcon::Default,
+        CursorIcon::Crosshair => winit::window::CursorIcon::Crosshair,
+        CursorIcon::Move => winit::window::CursorIcon::Move,
+        CursorIcon::Text => winit::window::CursorIcon::Text,
+        CursorIcon::Wait => winit::window::CursorIcon::Wait,
+        CursorIcon::Help => winit::window::CursorIcon::Help,
+        CursorIcon::Progress => winit::window::CursorIcon::Progress,
+        CursorIcon::NotAllowed => winit::window::CursorIcon::NotAllowed,
+        CursorIcon::ContextMenu => winit::window::CursorIcon::ContextMenu,
+        CursorIcon::Cell => winit::window::CursorIcon::Cell,
+        CursorIcon::VerticalText => winit::window::CursorIcon::VerticalText,
+        CursorIcon::Alias => winit::window::CursorIcon::Alias,
+        CursorIcon::Copy => winit::window::CursorIcon::Copy,
+        CursorIcon::NoDrop => winit::window::CursorIcon::NoDrop,
+        CursorIcon::Grab => winit::window::CursorIcon::Grab,
+        CursorIcon::Grabbing => winit::window::CursorIcon::Grabbing,
+        CursorIcon::AllScroll => winit::window::CursorIcon::AllScroll,
+        CursorIcon::ZoomIn => winit::window::CursorIcon::ZoomIn,
+        CursorIcon::ZoomOut => winit::window::CursorIcon::ZoomOut,
+        CursorIcon::EResize => winit::window::CursorIcon::EResize,
+        CursorIcon::NResize => winit::window::CursorIcon::NResize,
+        CursorIcon::NeResize => winit::window::CursorIcon::NeResize,
+        CursorIcon::NwResize => winit::window::CursorIcon::NwResize,
+        CursorIcon::SResize => winit::window::CursorIcon::SResize,
+        CursorIcon::SeResize => winit::window::CursorIcon::SeResize,
+        CursorIcon::SwResize => winit::window::CursorIcon::SwResize,
+        CursorIcon::WResize => winit::window::CursorIcon::WResize,
+        CursorIcon::EwResize => winit::window::CursorIcon::EwResize,
+        CursorIcon::NsResize => winit::window::CursorIcon::NsResize,
+        CursorIcon::NeswResize => winit::window::CursorIcon::NeswResize,
+        CursorIcon::NwseResize => winit::window::CursorIcon::NwseResize,
+        CursorIcon::ColResize => winit::window::CursorIcon::ColResize,
+        CursorIcon::RowResize => winit::window::CursorIcon::RowResize,
+    }
+}
@@ -1,5 +1,6 @@
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Axis {
     X,
     Y
@@ -54,4 +55,11 @@ impl<T> PerAxis<T> {
         } 
     }
 
+    pub fn on_axis_mut(&mut self, axis: Axis) -> &mut T {
+        match axis {
+            Axis::X => &mut self.x,
+            Axis::Y => &mut self.y,
+        } 
+    }
+
 }
@@ -1,7 +1,7 @@
 
 use std::fmt::Display;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
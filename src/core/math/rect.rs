@@ -6,7 +6,7 @@ use crate::Animatable;
 
 use super::{vec2, Axis, Margin, Range, Vec2};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Rect {
     min: Vec2,
     max: Vec2
@@ -8,6 +8,12 @@ pub use app::*;
 mod paint;
 pub use paint::*;
 
+mod render;
+pub use render::*;
+
+mod integration;
+pub use integration::*;
+
 mod render_resources;
 pub(crate) use render_resources::*;
 
@@ -16,5 +22,14 @@ pub mod text;
 mod ui;
 pub use ui::*;
 
+mod headless;
+pub use headless::*;
+
+mod snapshot;
+pub use snapshot::*;
+
+mod record;
+pub use record::*;
+
 pub use wgpu;
 pub use cosmic_text;
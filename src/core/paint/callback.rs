@@ -0,0 +1,22 @@
+
+use std::any::Any;
+
+use crate::Rect;
+
+use super::{PaintCallback, Painter, Primitive};
+
+impl Painter<'_> {
+
+    /// Add a custom callback to the display list, covering a given rectangle.
+    /// The renderer decides what to do with the callback based on its type.
+    pub fn callback<T: Any>(&mut self, rect: Rect, callback: T) {
+        let rect = self.curr_transform() * rect;
+        let clip_rect = self.curr_clip_rect();
+        self.display_list.primitives.push(Primitive::Callback(PaintCallback {
+            rect,
+            clip_rect,
+            callback: Box::new(callback)
+        }));
+    }
+
+}
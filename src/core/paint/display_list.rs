@@ -0,0 +1,147 @@
+
+use std::{any::Any, fmt::Debug};
+
+use crate::{Color, Rect, Vec2};
+
+use super::{Stroke, Texture};
+
+/// The texture sampled by a primitive in the display list
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PaintTexture {
+    /// A page of the font atlas
+    FontAtlas(usize),
+    /// A user-provided texture
+    Texture(Texture)
+}
+
+/// A rectangle in the display list.
+/// All coordinates are in logical pixels, with transformations already applied.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RectPrimitive {
+    pub rect: Rect,
+    pub fill: Color,
+    pub texture: Option<PaintTexture>,
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+    pub rounding: f32,
+    pub stroke: Stroke,
+    pub clip_rect: Rect
+}
+
+/// A single glyph of painted text, sampled from a page of the font atlas.
+/// All coordinates are in logical pixels, with transformations already applied.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GlyphPrimitive {
+    pub rect: Rect,
+    pub color: Color,
+    pub atlas_page: usize,
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+    pub clip_rect: Rect
+}
+
+/// Custom paint code, run by the renderer when it reaches this point in the display list.
+/// Each renderer decides which kinds of callbacks it understands, and ignores the rest.
+pub struct PaintCallback {
+    pub rect: Rect,
+    pub clip_rect: Rect,
+    pub(crate) callback: Box<dyn Any>
+}
+
+impl PaintCallback {
+
+    /// Does this callback contain a callback of type `T`?
+    pub fn is<T: Any>(&self) -> bool {
+        self.callback.is::<T>()
+    }
+
+    /// Take the callback out, if it is of type `T`
+    pub fn take<T: Any>(self) -> Option<T> {
+        self.callback.downcast().ok().map(|callback| *callback)
+    }
+
+}
+
+impl Debug for PaintCallback {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaintCallback")
+            .field("rect", &self.rect)
+            .field("clip_rect", &self.clip_rect)
+            .finish_non_exhaustive()
+    }
+
+}
+
+#[derive(Debug)]
+pub enum Primitive {
+    Rect(RectPrimitive),
+    Glyph(GlyphPrimitive),
+    Callback(PaintCallback)
+}
+
+impl Primitive {
+
+    /// The area covered by the primitive, before clipping
+    pub fn rect(&self) -> Rect {
+        match self {
+            Primitive::Rect(rect) => rect.rect,
+            Primitive::Glyph(glyph) => glyph.rect,
+            Primitive::Callback(callback) => callback.rect,
+        }
+    }
+
+    /// The rectangle the primitive is clipped to
+    pub fn clip_rect(&self) -> Rect {
+        match self {
+            Primitive::Rect(rect) => rect.clip_rect,
+            Primitive::Glyph(glyph) => glyph.clip_rect,
+            Primitive::Callback(callback) => callback.clip_rect,
+        }
+    }
+
+}
+
+/// Everything painted in a frame, in the order it should be drawn.
+/// Produced by the `Painter` and consumed by a renderer.
+#[derive(Debug)]
+pub struct DisplayList {
+    pub primitives: Vec<Primitive>,
+    /// The size of the screen in logical pixels
+    pub screen_size: Vec2,
+    /// The number of physical pixels per logical pixel
+    pub scale_factor: f32
+}
+
+impl DisplayList {
+
+    pub fn new(screen_size: Vec2, scale_factor: f32) -> Self {
+        Self {
+            primitives: Vec::new(),
+            screen_size,
+            scale_factor
+        }
+    }
+
+    pub fn rects(&self) -> impl Iterator<Item = &RectPrimitive> {
+        self.primitives.iter().filter_map(|primitive| match primitive {
+            Primitive::Rect(rect) => Some(rect),
+            _ => None
+        })
+    }
+
+    pub fn glyphs(&self) -> impl Iterator<Item = &GlyphPrimitive> {
+        self.primitives.iter().filter_map(|primitive| match primitive {
+            Primitive::Glyph(glyph) => Some(glyph),
+            _ => None
+        })
+    }
+
+    pub fn callbacks(&self) -> impl Iterator<Item = &PaintCallback> {
+        self.primitives.iter().filter_map(|primitive| match primitive {
+            Primitive::Callback(callback) => Some(callback),
+            _ => None
+        })
+    }
+
+}
@@ -16,86 +16,37 @@ pub use rect::*;
 mod text;
 pub use text::*;
 
+mod display_list;
+pub use display_list::*;
+
+mod callback;
 mod clip;
 mod transform;
 
 use crate::{text::TextResources, Rect, Vec2};
 
-pub(crate) struct PainterResources {
-    rect: RectResources
-}
-
-impl PainterResources {
-
-    pub(crate) fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
-        let rect = RectResources::new(device, surface_format);
-        Self {
-            rect
-        }
-    }
-
-    pub(super) fn begin_frame(&mut self, queue: &wgpu::Queue, screen_size: Vec2) {
-        self.rect.begin_frame(queue, screen_size);
-    }
-
-}
-
+/// Records everything painted during a frame into a `DisplayList`
 pub struct Painter<'a> {
-    device: &'a wgpu::Device,
-    queue: &'a wgpu::Queue,
-    render_pass: wgpu::RenderPass<'a>,
-    resources: &'a mut PainterResources,
     text_resources: &'a mut TextResources,
+    display_list: DisplayList,
 
     dpi_scale: f32,
 
     clip_stack: Vec<Rect>,
     transform_stack: Vec<TSTransform>,
 
+    /// The glyph layouts of the text painted in the window last frame. Layouts are moved to `next_text_render_cache` as they are used
     text_render_cache: &'a mut TextRenderCache,
-    next_text_render_cache: &'a mut TextRenderCache
+    next_text_render_cache: TextRenderCache
 }
 
 impl<'a> Painter<'a> {
 
-    pub(crate) fn new(
-        device: &'a wgpu::Device, 
-        queue: &'a wgpu::Queue,
-        encoder: &'a mut wgpu::CommandEncoder,
-        view: &'a wgpu::TextureView,
-
-        resources: &'a mut PainterResources,
-        text_resources: &'a mut TextResources,
-
-        size: Vec2,
-        dpi_scale: f32,
-
-        text_render_cache: &'a mut TextRenderCache,
-        next_text_render_cache: &'a mut TextRenderCache
-    ) -> Self {
-
-        let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("pierro_paint_render_pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.5, g: 0.5, b: 0.5, a: 1.0 }),
-                    store: wgpu::StoreOp::Store
-                }
-            })],
-            depth_stencil_attachment: None,
-            timestamp_writes: None,
-            occlusion_query_set: None,
-        });
-
+    /// Create a painter for a window. Each window has its own text render cache, so that windows do not evict each other's text.
+    pub(crate) fn new(text_resources: &'a mut TextResources, text_render_cache: &'a mut TextRenderCache, size: Vec2, dpi_scale: f32) -> Self {
         Self {
-            device,
-            queue,
-            render_pass,
-
-            resources,
             text_resources,
+            display_list: DisplayList::new(size, dpi_scale),
 
             dpi_scale,
 
@@ -103,13 +54,18 @@ impl<'a> Painter<'a> {
             transform_stack: vec![TSTransform::IDENTITY],
 
             text_render_cache,
-            next_text_render_cache
+            next_text_render_cache: TextRenderCache::new()
         }
     }
 
-    pub(super) fn finish(mut self) {
-        self.resources.rect.flush_buffer(&self.device, self.queue, &mut self.render_pass);
-        self.resources.rect.finish();
+    pub(crate) fn finish(self) -> DisplayList {
+        *self.text_render_cache = self.next_text_render_cache;
+        self.display_list
+    }
+
+    /// The primitives painted so far
+    pub fn display_list(&self) -> &DisplayList {
+        &self.display_list
     }
 
 }
@@ -1,7 +1,7 @@
 
 use crate::Color;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Stroke {
     pub color: Color,
     pub width: f32
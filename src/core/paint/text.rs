@@ -5,7 +5,7 @@ use cosmic_text::SubpixelBin;
 
 use crate::{text::FontId, vec2, Color, Rect, Vec2};
 
-use super::{PaintRect, Painter, Texture};
+use super::{GlyphPrimitive, Painter, Primitive};
 
 #[derive(Clone, Copy)]
 pub struct TextStyle {
@@ -57,7 +57,7 @@ struct TextRenderCacheKey {
 
 #[derive(Clone)]
 struct TextRenderCacheGlyph {
-    texture: Texture,
+    page: usize,
     pos: Vec2,
     size: Vec2,
     uv_min: Vec2,
@@ -99,15 +99,22 @@ fn f32_to_i64_key(val: f32) -> i64 {
 impl Painter<'_> {
 
     fn render_text(&mut self, layout: &TextRenderLayout, pos: Vec2, color: Color) {
+        let transform = self.curr_transform();
+        let clip_rect = self.curr_clip_rect();
         for glyph in &layout.glyphs {
             let rect = Rect::min_size(
                 glyph.pos + pos,
                 glyph.size 
             );
 
-            self.rect(PaintRect::new(rect, if glyph.color { Color::white_alpha(color.a) } else { color })
-                .with_texture(glyph.texture.clone())
-                .with_uv(glyph.uv_min, glyph.uv_max));
+            self.display_list.primitives.push(Primitive::Glyph(GlyphPrimitive {
+                rect: transform * rect,
+                color: if glyph.color { Color::white_alpha(color.a) } else { color },
+                atlas_page: glyph.page,
+                uv_min: glyph.uv_min,
+                uv_max: glyph.uv_max,
+                clip_rect
+            }));
         }
     }
 
@@ -155,16 +162,16 @@ impl Painter<'_> {
                 let mut physical_glyph = glyph.physical((0.0, 0.0), 1.0);
                 physical_glyph.cache_key.x_bin = SubpixelBin::Zero;
                 physical_glyph.cache_key.y_bin = SubpixelBin::Zero;
-                if let Some(glyph_info) = self.text_resources.get_glyph(text.style.font, physical_glyph.cache_key, self.device, self.queue) {
+                if let Some(glyph_info) = self.text_resources.atlas.get_glyph(physical_glyph.cache_key, font_system, &mut self.text_resources.swash_cache) {
                     let pos = (vec2(physical_glyph.x as f32, physical_glyph.y as f32 + run.line_y) + glyph_info.data.pos) / self.dpi_scale;
                     let size = glyph_info.data.size / self.dpi_scale;
-                    let texture = glyph_info.texture.clone();
+                    let page = glyph_info.page;
                     let uv_min = glyph_info.data.uv_min;
                     let uv_max = glyph_info.data.uv_max;
                     let color = glyph_info.data.color;
 
                     glyphs.push(TextRenderCacheGlyph {
-                        texture,
+                        page,
                         pos,
                         size,
                         uv_min,
@@ -1,5 +1,5 @@
 
-use std::sync::Arc;
+use std::{fmt::Debug, sync::Arc};
 
 #[derive(Clone)]
 pub struct Texture {
@@ -16,6 +16,14 @@ impl PartialEq for Texture {
 
 impl Eq for Texture {}
 
+impl Debug for Texture {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Texture").field(&Arc::as_ptr(&self.tex)).finish()
+    }
+
+}
+
 impl Texture {
 
     pub fn new(texture: wgpu::Texture, texture_view: wgpu::TextureView) -> Self {
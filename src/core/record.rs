@@ -0,0 +1,332 @@
+
+use std::{fs::File, io::{self, BufReader, BufWriter, Read, Write}, path::Path};
+
+use crate::{vec2, Key, KeyCode, KeyEvent, LogicalKey, Modifiers, PenInput, RawInput, Vec2};
+
+/// Set this environment variable to a file path to record the input of `pierro::run` into it
+pub const RECORD_INPUT_ENV_VAR: &str = "PIERRO_RECORD_INPUT";
+
+const MAGIC: &[u8; 4] = b"PIRI";
+const VERSION: u32 = 1;
+
+/// The longest string a recording can contain, e.g. for pasted text
+const MAX_STRING_LEN: u64 = 64 * 1024 * 1024;
+
+/// The input given to a single frame of the UI
+#[derive(Clone, PartialEq, Debug)]
+pub struct RecordedFrame {
+    /// The raw input, as it was before being given to the UI
+    pub raw_input: RawInput,
+    /// The logical size of the window
+    pub window_size: Vec2,
+    pub scale_factor: f32
+}
+
+/// A sequence of frames of input, which can be replayed using `Headless::replay`.
+/// Stored on disk in a simple binary format.
+#[derive(Clone, PartialEq, Debug)]
+pub struct InputRecording {
+    pub frames: Vec<RecordedFrame>
+}
+
+impl InputRecording {
+
+    pub fn new() -> Self {
+        Self {
+            frames: Vec::new()
+        }
+    }
+
+    pub fn push(&mut self, frame: RecordedFrame) {
+        self.frames.push(frame);
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_header(&mut writer)?;
+        for frame in &self.frames {
+            write_frame(&mut writer, frame)?;
+        }
+        writer.flush()
+    }
+
+    /// Load a recording from a file.
+    /// If the recording was cut short, e.g. because the app crashed, the frames before the cut are kept.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a pierro input recording"));
+        }
+        let version = read_u32(&mut reader)?;
+        if version != VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unsupported input recording version {}", version)));
+        }
+
+        let mut frames = Vec::new();
+        loop {
+            match read_frame(&mut reader) {
+                Ok(frame) => frames.push(frame),
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err)
+            }
+        }
+
+        Ok(Self {
+            frames
+        })
+    }
+
+}
+
+impl Default for InputRecording {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
+
+/// Writes frames of input to a file as they happen, so that the recording survives crashes
+pub struct InputRecorder {
+    writer: BufWriter<File>
+}
+
+impl InputRecorder {
+
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_header(&mut writer)?;
+        writer.flush()?;
+        Ok(Self {
+            writer
+        })
+    }
+
+    pub fn record(&mut self, frame: &RecordedFrame) -> io::Result<()> {
+        // Encode the whole frame first, so that a frame that cannot be recorded leaves the file intact
+        let mut bytes = Vec::new();
+        write_frame(&mut bytes, frame)?;
+        self.writer.write_all(&bytes)?;
+        self.writer.flush()
+    }
+
+}
+
+fn write_header<W: Write>(writer: &mut W) -> io::Result<()> {
+    writer.write_all(MAGIC)?;
+    write_u32(writer, VERSION)
+}
+
+fn write_u32<W: Write>(writer: &mut W, val: u32) -> io::Result<()> {
+    writer.write_all(&val.to_le_bytes())
+}
+
+fn write_f32<W: Write>(writer: &mut W, val: f32) -> io::Result<()> {
+    writer.write_all(&val.to_le_bytes())
+}
+
+fn write_bool<W: Write>(writer: &mut W, val: bool) -> io::Result<()> {
+    writer.write_all(&[val as u8])
+}
+
+fn write_vec2<W: Write>(writer: &mut W, val: Vec2) -> io::Result<()> {
+    write_f32(writer, val.x)?;
+    write_f32(writer, val.y)
+}
+
+fn write_string<W: Write>(writer: &mut W, val: &str) -> io::Result<()> {
+    write_u32(writer, val.len() as u32)?;
+    writer.write_all(val.as_bytes())
+}
+
+fn write_option<W: Write, T, F: FnOnce(&mut W, &T) -> io::Result<()>>(writer: &mut W, val: &Option<T>, write: F) -> io::Result<()> {
+    write_bool(writer, val.is_some())?;
+    match val {
+        Some(val) => write(writer, val),
+        None => Ok(())
+    }
+}
+
+fn write_key<W: Write>(writer: &mut W, key: &Key) -> io::Result<()> {
+    write_option(writer, &key.text, |writer, text| write_string(writer, text))?;
+    write_option(writer, &key.logical_key, |writer, logical_key| {
+        let idx = LogicalKey::ALL.iter().position(|other| other == logical_key)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "logical key cannot be recorded"))?;
+        write_u32(writer, idx as u32)
+    })
+}
+
+fn write_modifiers<W: Write>(writer: &mut W, modifiers: Modifiers) -> io::Result<()> {
+    let bits = [
+        modifiers.left_ctrl, modifiers.right_ctrl,
+        modifiers.left_shift, modifiers.right_shift,
+        modifiers.left_alt, modifiers.right_alt,
+        modifiers.left_logo, modifiers.right_logo
+    ].iter().enumerate().fold(0u8, |bits, (idx, down)| bits | ((*down as u8) << idx));
+    writer.write_all(&[bits])
+}
+
+fn write_key_events<W: Write>(writer: &mut W, events: &[KeyEvent]) -> io::Result<()> {
+    write_u32(writer, events.len() as u32)?;
+    for event in events {
+        write_key(writer, &event.key)?;
+        write_option(writer, &event.physical_key, |writer, physical_key| {
+            let idx = KeyCode::ALL.iter().position(|other| other == physical_key)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "key code cannot be recorded"))?;
+            write_u32(writer, idx as u32)
+        })?;
+        write_bool(writer, event.pressed)?;
+        write_modifiers(writer, event.modifiers)?;
+    }
+    Ok(())
+}
+
+fn write_pen<W: Write>(writer: &mut W, pen: &PenInput) -> io::Result<()> {
+    write_vec2(writer, pen.position)?;
+    write_f32(writer, pen.pressure)?;
+    write_option(writer, &pen.altitude, |writer, altitude| write_f32(writer, *altitude))
+}
+
+fn write_frame<W: Write>(writer: &mut W, frame: &RecordedFrame) -> io::Result<()> {
+    write_vec2(writer, frame.window_size)?;
+    write_f32(writer, frame.scale_factor)?;
+
+    let raw_input = &frame.raw_input;
+    write_f32(writer, raw_input.delta_time)?;
+    write_option(writer, &raw_input.mouse_pos, |writer, pos| write_vec2(writer, *pos))?;
+    write_bool(writer, raw_input.l_mouse_down)?;
+    write_bool(writer, raw_input.r_mouse_down)?;
+    write_vec2(writer, raw_input.scroll)?;
+    write_key_events(writer, &raw_input.key_events)?;
+    write_modifiers(writer, raw_input.modifiers)?;
+    write_string(writer, &raw_input.ime_preedit)?;
+    write_option(writer, &raw_input.ime_commit, |writer, text| write_string(writer, text))?;
+    write_bool(writer, raw_input.m_mouse_down)?;
+    write_bool(writer, raw_input.back_mouse_down)?;
+    write_bool(writer, raw_input.forward_mouse_down)?;
+    write_option(writer, &raw_input.pen, write_pen)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f32<R: Read>(reader: &mut R) -> io::Result<f32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(f32::from_le_bytes(bytes))
+}
+
+fn read_bool<R: Read>(reader: &mut R) -> io::Result<bool> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0] != 0)
+}
+
+fn read_vec2<R: Read>(reader: &mut R) -> io::Result<Vec2> {
+    let x = read_f32(reader)?;
+    let y = read_f32(reader)?;
+    Ok(vec2(x, y))
+}
+
+fn read_string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let len = read_u32(reader)? as u64;
+    if len > MAX_STRING_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("string of {} bytes is too long", len)));
+    }
+    // Only allocate as much as the recording actually contains, in case it is truncated or corrupt
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "input recording ends in the middle of a string"));
+    }
+    String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn read_option<R: Read, T, F: FnOnce(&mut R) -> io::Result<T>>(reader: &mut R, read: F) -> io::Result<Option<T>> {
+    if read_bool(reader)? {
+        Ok(Some(read(reader)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn read_key<R: Read>(reader: &mut R) -> io::Result<Key> {
+    let text = read_option(reader, read_string)?;
+    let logical_key = read_option(reader, |reader| {
+        let idx = read_u32(reader)? as usize;
+        LogicalKey::ALL.get(idx).copied().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid logical key"))
+    })?;
+    Ok(Key { text, logical_key })
+}
+
+fn read_modifiers<R: Read>(reader: &mut R) -> io::Result<Modifiers> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    let bit = |idx: u32| bytes[0] & (1 << idx) != 0;
+    Ok(Modifiers {
+        left_ctrl: bit(0),
+        right_ctrl: bit(1),
+        left_shift: bit(2),
+        right_shift: bit(3),
+        left_alt: bit(4),
+        right_alt: bit(5),
+        left_logo: bit(6),
+        right_logo: bit(7)
+    })
+}
+
+fn read_key_events<R: Read>(reader: &mut R) -> io::Result<Vec<KeyEvent>> {
+    let n_events = read_u32(reader)?;
+    let mut events = Vec::new();
+    for _ in 0..n_events {
+        let key = read_key(reader)?;
+        let physical_key = read_option(reader, |reader| {
+            let idx = read_u32(reader)? as usize;
+            KeyCode::ALL.get(idx).copied().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid key code"))
+        })?;
+        let pressed = read_bool(reader)?;
+        let modifiers = read_modifiers(reader)?;
+        events.push(KeyEvent { key, physical_key, pressed, modifiers });
+    }
+    Ok(events)
+}
+
+fn read_pen<R: Read>(reader: &mut R) -> io::Result<PenInput> {
+    Ok(PenInput {
+        position: read_vec2(reader)?,
+        pressure: read_f32(reader)?,
+        altitude: read_option(reader, read_f32)?
+    })
+}
+
+fn read_frame<R: Read>(reader: &mut R) -> io::Result<RecordedFrame> {
+    let window_size = read_vec2(reader)?;
+    let scale_factor = read_f32(reader)?;
+
+    let mut raw_input = RawInput::new();
+    raw_input.delta_time = read_f32(reader)?;
+    raw_input.mouse_pos = read_option(reader, read_vec2)?;
+    raw_input.l_mouse_down = read_bool(reader)?;
+    raw_input.r_mouse_down = read_bool(reader)?;
+    raw_input.scroll = read_vec2(reader)?;
+    raw_input.key_events = read_key_events(reader)?;
+    raw_input.modifiers = read_modifiers(reader)?;
+    raw_input.ime_preedit = read_string(reader)?;
+    raw_input.ime_commit = read_option(reader, read_string)?;
+    raw_input.m_mouse_down = read_bool(reader)?;
+    raw_input.back_mouse_down = read_bool(reader)?;
+    raw_input.forward_mouse_down = read_bool(reader)?;
+    raw_input.pen = read_option(reader, read_pen)?;
+
+    Ok(RecordedFrame {
+        raw_input,
+        window_size,
+        scale_factor
+    })
+}
@@ -0,0 +1,198 @@
+
+mod rect;
+use rect::*;
+
+mod software;
+pub use software::*;
+
+use crate::{text::FontAtlas, Color, DisplayList, PaintTexture, Primitive, Rect, Texture};
+
+/// Context given to a `WgpuCallback` when it is run by the renderer
+pub struct WgpuCallbackContext<'a, 'b> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub render_pass: &'a mut wgpu::RenderPass<'b>,
+    /// The rectangle covered by the callback, in logical pixels
+    pub rect: Rect,
+    /// The rectangle the callback is clipped to, in logical pixels.
+    /// The render pass's scissor rect is already set to this.
+    pub clip_rect: Rect,
+    /// The number of physical pixels per logical pixel
+    pub scale_factor: f32
+}
+
+/// A paint callback understood by the wgpu renderer.
+/// Pass it to `Painter::callback` to draw custom wgpu content in the middle of the UI.
+pub struct WgpuCallback(Box<dyn FnOnce(&mut WgpuCallbackContext)>);
+
+impl WgpuCallback {
+
+    pub fn new<F: FnOnce(&mut WgpuCallbackContext) + 'static>(callback: F) -> Self {
+        Self(Box::new(callback))
+    }
+
+}
+
+/// A texture view to render into, along with the size of its texture in physical pixels
+pub(crate) struct RenderTarget<'a> {
+    pub(crate) view: &'a wgpu::TextureView,
+    pub(crate) size: (u32, u32)
+}
+
+/// Draws display lists using wgpu
+pub(crate) struct WgpuRenderer {
+    rect: RectResources,
+    font_atlas_textures: Vec<Texture>,
+    /// The color the target is cleared to. If `None`, the target is not cleared
+    pub(crate) clear_color: Option<Color>
+}
+
+impl WgpuRenderer {
+
+    pub(crate) fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
+        Self {
+            rect: RectResources::new(device, surface_format),
+            font_atlas_textures: Vec::new(),
+            clear_color: Some(Color::rgb(0.5, 0.5, 0.5))
+        }
+    }
+
+    /// Upload the parts of the font atlas that changed since the last frame
+    fn upload_font_atlas(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, atlas: &mut FontAtlas) {
+        for (idx, page) in atlas.pages.iter_mut().enumerate() {
+            let size = page.size();
+            if idx == self.font_atlas_textures.len() {
+                let texture = device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("pierro_font_atlas_texture"),
+                    size: wgpu::Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                    view_formats: &[],
+                });
+                let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                self.font_atlas_textures.push(Texture::new(texture, texture_view));
+            }
+
+            let Some(dirty) = page.dirty.take() else { continue; };
+            if dirty.max_x <= dirty.min_x || dirty.max_y <= dirty.min_y {
+                continue;
+            }
+
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: self.font_atlas_textures[idx].texture(),
+                    mip_level: 0,
+                    origin: wgpu::Origin3d { x: dirty.min_x, y: dirty.min_y, z: 0 },
+                    aspect: wgpu::TextureAspect::All
+                },
+                page.image.as_raw(),
+                wgpu::ImageDataLayout {
+                    offset: ((dirty.min_y * size + dirty.min_x) * 4) as u64,
+                    bytes_per_row: Some(size * 4),
+                    rows_per_image: None
+                },
+                wgpu::Extent3d {
+                    width: dirty.max_x - dirty.min_x,
+                    height: dirty.max_y - dirty.min_y,
+                    depth_or_array_layers: 1
+                }
+            );
+        }
+    }
+
+    /// Set the scissor rect of the render pass to a logical rectangle, returning false if nothing is visible
+    fn set_scissor_rect(render_pass: &mut wgpu::RenderPass, rect: Rect, scale_factor: f32, target_size: (u32, u32)) -> bool {
+        let min_x = ((rect.left() * scale_factor).floor().max(0.0) as u32).min(target_size.0);
+        let min_y = ((rect.top() * scale_factor).floor().max(0.0) as u32).min(target_size.1);
+        let max_x = ((rect.right() * scale_factor).ceil().max(0.0) as u32).min(target_size.0);
+        let max_y = ((rect.bottom() * scale_factor).ceil().max(0.0) as u32).min(target_size.1);
+        if max_x <= min_x || max_y <= min_y {
+            return false;
+        }
+        render_pass.set_scissor_rect(min_x, min_y, max_x - min_x, max_y - min_y);
+        true
+    }
+
+    pub(crate) fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target: RenderTarget,
+
+        atlas: &mut FontAtlas,
+        display_list: DisplayList
+    ) {
+        let scale_factor = display_list.scale_factor;
+        let target_size = target.size;
+
+        self.upload_font_atlas(device, queue, atlas);
+        self.rect.begin_frame(queue, display_list.screen_size);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("pierro_paint_render_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: match self.clear_color {
+                        Some(color) => wgpu::LoadOp::Clear(wgpu::Color { r: color.r as f64, g: color.g as f64, b: color.b as f64, a: color.a as f64 }),
+                        None => wgpu::LoadOp::Load
+                    },
+                    store: wgpu::StoreOp::Store
+                }
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        for primitive in display_list.primitives {
+            match primitive {
+                Primitive::Rect(rect) => {
+                    let texture = match &rect.texture {
+                        Some(PaintTexture::FontAtlas(page)) => self.font_atlas_textures.get(*page),
+                        Some(PaintTexture::Texture(texture)) => Some(texture),
+                        None => None,
+                    };
+                    let data = RectData::new(rect.rect, rect.fill, rect.uv_min, rect.uv_max, rect.clip_rect)
+                        .with_rounding(rect.rounding)
+                        .with_stroke(rect.stroke);
+                    self.rect.push_rect(data, texture, device, queue, &mut render_pass);
+                },
+                Primitive::Glyph(glyph) => {
+                    let Some(texture) = self.font_atlas_textures.get(glyph.atlas_page) else { continue; };
+                    let data = RectData::new(glyph.rect, glyph.color, glyph.uv_min, glyph.uv_max, glyph.clip_rect);
+                    self.rect.push_rect(data, Some(texture), device, queue, &mut render_pass);
+                },
+                Primitive::Callback(callback) => {
+                    let rect = callback.rect;
+                    let clip_rect = callback.clip_rect;
+                    let Some(callback) = callback.take::<WgpuCallback>() else { continue; };
+
+                    // Draw everything painted before the callback first
+                    self.rect.flush_buffer(device, queue, &mut render_pass);
+
+                    if Self::set_scissor_rect(&mut render_pass, clip_rect, scale_factor, target_size) {
+                        (callback.0)(&mut WgpuCallbackContext {
+                            device,
+                            queue,
+                            render_pass: &mut render_pass,
+                            rect,
+                            clip_rect,
+                            scale_factor
+                        });
+                        render_pass.set_scissor_rect(0, 0, target_size.0, target_size.1);
+                    }
+                }
+            }
+        }
+
+        self.rect.flush_buffer(device, queue, &mut render_pass);
+        self.rect.finish();
+    }
+
+}
@@ -0,0 +1,224 @@
+
+use crate::{text::FontAtlas, vec2, Color, DisplayList, PaintTexture, Primitive, Rect, Stroke, Texture, Vec2};
+
+/// Renders display lists into an image on the CPU.
+/// Produces the same output as the wgpu renderer, for machines without a GPU.
+pub struct SoftwareRenderer {
+    clear_color: Color,
+    /// CPU copies of user textures, since the software renderer cannot read wgpu textures
+    texture_images: Vec<(Texture, image::RgbaImage)>
+}
+
+/// A rectangle to rasterize, equivalent to the data given to `rect.wgsl`
+struct RasterRect<'a> {
+    rect: Rect,
+    color: Color,
+    uv_min: Vec2,
+    uv_max: Vec2,
+    texture: Option<&'a image::RgbaImage>,
+    clip_rect: Rect,
+    rounding: f32,
+    stroke: Stroke
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn rounded_rect_sdf(pos: Vec2, rect_center: Vec2, rect_half_size: Vec2, r: f32) -> f32 {
+    let d2 = (rect_center - pos).abs() - rect_half_size + Vec2::splat(r);
+    d2.x.max(d2.y).min(0.0) + d2.max(Vec2::ZERO).length() - r
+}
+
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    Color::rgba(
+        a.r + (b.r - a.r) * t,
+        a.g + (b.g - a.g) * t,
+        a.b + (b.b - a.b) * t,
+        a.a + (b.a - a.a) * t
+    )
+}
+
+fn pixel_to_color(pixel: image::Rgba<u8>) -> Color {
+    Color::rgba(
+        pixel.0[0] as f32 / 255.0,
+        pixel.0[1] as f32 / 255.0,
+        pixel.0[2] as f32 / 255.0,
+        pixel.0[3] as f32 / 255.0
+    )
+}
+
+fn color_to_pixel(color: Color) -> image::Rgba<u8> {
+    let to_byte = |val: f32| (val.clamp(0.0, 1.0) * 255.0).round() as u8;
+    image::Rgba([to_byte(color.r), to_byte(color.g), to_byte(color.b), to_byte(color.a)])
+}
+
+/// Bilinearly sample an image with clamp-to-edge addressing, like the wgpu sampler
+fn sample(image: &image::RgbaImage, uv: Vec2) -> Color {
+    let size = vec2(image.width() as f32, image.height() as f32);
+    let pos = uv * size - Vec2::splat(0.5);
+    let x0 = pos.x.floor();
+    let y0 = pos.y.floor();
+    let tx = pos.x - x0;
+    let ty = pos.y - y0;
+
+    let texel = |x: f32, y: f32| {
+        let x = (x.max(0.0) as u32).min(image.width() - 1);
+        let y = (y.max(0.0) as u32).min(image.height() - 1);
+        pixel_to_color(*image.get_pixel(x, y))
+    };
+
+    let top = mix(texel(x0, y0), texel(x0 + 1.0, y0), tx);
+    let bottom = mix(texel(x0, y0 + 1.0), texel(x0 + 1.0, y0 + 1.0), tx);
+    mix(top, bottom, ty)
+}
+
+impl SoftwareRenderer {
+
+    pub fn new() -> Self {
+        Self {
+            clear_color: Color::rgb(0.5, 0.5, 0.5),
+            texture_images: Vec::new()
+        }
+    }
+
+    /// Set the color the image is filled with before anything is drawn
+    pub fn with_clear_color(mut self, clear_color: Color) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+
+    /// Provide the contents of a user texture.
+    /// Textures without an image are drawn as if they were solid white.
+    pub fn set_texture_image(&mut self, texture: Texture, image: image::RgbaImage) {
+        self.remove_texture_image(&texture);
+        self.texture_images.push((texture, image));
+    }
+
+    pub fn remove_texture_image(&mut self, texture: &Texture) {
+        self.texture_images.retain(|(other, _)| other != texture);
+    }
+
+    fn texture_image(&self, texture: &Texture) -> Option<&image::RgbaImage> {
+        self.texture_images.iter().find(|(other, _)| other == texture).map(|(_, image)| image)
+    }
+
+    fn draw_rect(image: &mut image::RgbaImage, rect: RasterRect, scale_factor: f32) {
+        if rect.rect.width() <= 0.0 || rect.rect.height() <= 0.0 {
+            return;
+        }
+
+        // Only visit the physical pixels whose centers could be covered by the rect
+        let bounds = rect.rect.intersect(rect.clip_rect);
+        let min_x = ((bounds.left() * scale_factor - 0.5).ceil().max(0.0) as u32).min(image.width());
+        let min_y = ((bounds.top() * scale_factor - 0.5).ceil().max(0.0) as u32).min(image.height());
+        let max_x = ((bounds.right() * scale_factor + 0.5).ceil().max(0.0) as u32).min(image.width());
+        let max_y = ((bounds.bottom() * scale_factor + 0.5).ceil().max(0.0) as u32).min(image.height());
+
+        let center = rect.rect.center();
+        let half_size = rect.rect.size() * 0.5;
+        let inner_half_size = half_size - Vec2::splat(rect.stroke.width);
+        let inner_rounding = (rect.rounding - 2.0 * rect.stroke.width).max(0.0);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let pos = vec2(x as f32 + 0.5, y as f32 + 0.5) / scale_factor;
+
+                // The rect covers pixels whose centers lie inside it, like the GPU rasterizer
+                if pos.x < rect.rect.left() || pos.y < rect.rect.top() || pos.x >= rect.rect.right() || pos.y >= rect.rect.bottom() {
+                    continue;
+                }
+                if pos.x < rect.clip_rect.left() || pos.y < rect.clip_rect.top() || pos.x > rect.clip_rect.right() || pos.y > rect.clip_rect.bottom() {
+                    continue;
+                }
+
+                let outer_sdf = rounded_rect_sdf(pos, center, half_size, rect.rounding);
+                let rounding_factor = 1.0 - smoothstep(0.0, 1.0, outer_sdf);
+
+                let mut color = rect.color;
+                if rect.stroke.width > 0.0 {
+                    let inner_sdf = rounded_rect_sdf(pos, center, inner_half_size, inner_rounding);
+                    let stroke_fac = smoothstep(-0.5, 0.5, inner_sdf);
+                    color = mix(color, rect.stroke.color, stroke_fac);
+                }
+
+                if let Some(texture) = rect.texture {
+                    let t = (pos - rect.rect.tl()) / rect.rect.size();
+                    let uv = rect.uv_min + t * (rect.uv_max - rect.uv_min);
+                    let tex_color = sample(texture, uv);
+                    color = Color::rgba(color.r * tex_color.r, color.g * tex_color.g, color.b * tex_color.b, color.a * tex_color.a);
+                }
+                color.a *= rounding_factor;
+
+                // Standard alpha blending, matching the wgpu pipeline
+                let dst = pixel_to_color(*image.get_pixel(x, y));
+                let blended = Color::rgba(
+                    color.r * color.a + dst.r * (1.0 - color.a),
+                    color.g * color.a + dst.g * (1.0 - color.a),
+                    color.b * color.a + dst.b * (1.0 - color.a),
+                    color.a + dst.a * (1.0 - color.a)
+                );
+                image.put_pixel(x, y, color_to_pixel(blended));
+            }
+        }
+    }
+
+    /// Rasterize a display list into an image, in physical pixels.
+    /// Paint callbacks are ignored.
+    pub(crate) fn render(&self, display_list: &DisplayList, atlas: &FontAtlas) -> image::RgbaImage {
+        let scale_factor = display_list.scale_factor;
+        let physical_size = display_list.screen_size * scale_factor;
+        let width = physical_size.x.round().max(0.0) as u32;
+        let height = physical_size.y.round().max(0.0) as u32;
+
+        let mut image = image::RgbaImage::from_pixel(width, height, color_to_pixel(self.clear_color));
+
+        for primitive in &display_list.primitives {
+            match primitive {
+                Primitive::Rect(rect) => {
+                    let texture = match &rect.texture {
+                        Some(PaintTexture::FontAtlas(page)) => atlas.pages.get(*page).map(|page| &page.image),
+                        Some(PaintTexture::Texture(texture)) => self.texture_image(texture),
+                        None => None,
+                    };
+                    Self::draw_rect(&mut image, RasterRect {
+                        rect: rect.rect,
+                        color: rect.fill,
+                        uv_min: rect.uv_min,
+                        uv_max: rect.uv_max,
+                        texture,
+                        clip_rect: rect.clip_rect,
+                        rounding: rect.rounding.min(rect.rect.size().min_component() / 2.0),
+                        stroke: rect.stroke
+                    }, scale_factor);
+                },
+                Primitive::Glyph(glyph) => {
+                    let Some(page) = atlas.pages.get(glyph.atlas_page) else { continue; };
+                    Self::draw_rect(&mut image, RasterRect {
+                        rect: glyph.rect,
+                        color: glyph.color,
+                        uv_min: glyph.uv_min,
+                        uv_max: glyph.uv_max,
+                        texture: Some(&page.image),
+                        clip_rect: glyph.clip_rect,
+                        rounding: 0.0,
+                        stroke: Stroke::NONE
+                    }, scale_factor);
+                },
+                Primitive::Callback(_) => {}
+            }
+        }
+
+        image
+    }
+
+}
+
+impl Default for SoftwareRenderer {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
@@ -5,36 +5,52 @@ use winit::{
     dpi::{LogicalSize, PhysicalSize, Size}, event_loop::ActiveEventLoop, window::{Icon, Window, WindowAttributes}
 };
 
-use crate::{text::TextResources, PainterResources, WindowConfig};
-
-use super::{TextRenderCache, Vec2};
+use crate::WindowConfig;
 
 pub(crate) struct RenderResources<'a> {
     pub(crate) window: Arc<Window>,
 
+    instance: wgpu::Instance,
+    adapter: wgpu::Adapter,
     pub(crate) surface: wgpu::Surface<'a>,
     pub(crate) device: wgpu::Device,
     pub(crate) queue: wgpu::Queue,
     pub(crate) config: wgpu::SurfaceConfiguration,
-    pub(crate) physical_size: PhysicalSize<u32>,
+    pub(crate) physical_size: PhysicalSize<u32>
+}
 
-    pub(crate) text_resources: TextResources,
-    pub(crate) paint_resources: PainterResources,
+fn window_attributes(config: WindowConfig) -> WindowAttributes {
+    let icon = Icon::from_rgba(config.icon.rgba, config.icon.width, config.icon.height).ok();
+    WindowAttributes::default()
+        .with_min_inner_size(Size::Logical(LogicalSize::new(config.min_size.x as f64, config.min_size.y as f64)))
+        .with_window_icon(icon)
+        .with_title(config.title)
+}
+
+/// An additional window sharing the device of the main window
+pub(crate) struct WindowSurface {
+    pub(crate) window: Arc<Window>,
+    pub(crate) surface: wgpu::Surface<'static>,
+    config: wgpu::SurfaceConfiguration
+}
+
+impl WindowSurface {
+
+    pub(crate) fn resize(&mut self, device: &wgpu::Device, new_size: PhysicalSize<u32>) {
+        if new_size.width > 0 && new_size.height > 0 {
+            self.config.width = new_size.width;
+            self.config.height = new_size.height;
+            self.surface.configure(device, &self.config);
+        }
+    }
 
-    pub(crate) text_render_cache: TextRenderCache
 }
 
 impl RenderResources<'_> {
 
     pub(crate) async fn new(event_loop: &ActiveEventLoop, config: WindowConfig) -> Option<Self> {
 
-        let icon = Icon::from_rgba(config.icon.rgba, config.icon.width, config.icon.height).ok();
-
-        let window_attributes = WindowAttributes::default()
-            .with_min_inner_size(Size::Logical(LogicalSize::new(config.min_size.x as f64, config.min_size.y as f64)))
-            .with_window_icon(icon)
-            .with_title(config.title);
-        let window = Arc::new(event_loop.create_window(window_attributes).ok()?);
+        let window = Arc::new(event_loop.create_window(window_attributes(config)).ok()?);
         let size = window.inner_size();
 
         let instance = wgpu::Instance::default();
@@ -73,28 +89,18 @@ impl RenderResources<'_> {
             desired_maximum_frame_latency: 2,
         };
 
-        let paint_resources = PainterResources::new(&device, config.format);
-
-        let text_resources = TextResources::new(&device);
-        let text_render_cache = TextRenderCache::new();
-
         Some(Self {
             window,
+            instance,
+            adapter,
             surface,
             device,
             queue,
             config,
-            physical_size: size,
-            paint_resources,
-            text_resources,
-            text_render_cache
+            physical_size: size
         })
     }
 
-    pub(crate) fn begin_frame(&mut self, screen_size: Vec2) {
-        self.paint_resources.begin_frame(&self.queue, screen_size);
-    }
-
     pub(crate) fn resize(&mut self, new_size: PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
             self.physical_size = new_size;
@@ -104,6 +110,31 @@ impl RenderResources<'_> {
         }
     }
 
+    /// Create an additional window rendered with the same device and surface format as the main window
+    pub(crate) fn create_window(&self, event_loop: &ActiveEventLoop, config: WindowConfig) -> Option<WindowSurface> {
+        let window = Arc::new(event_loop.create_window(window_attributes(config)).ok()?);
+        let size = window.inner_size();
+
+        let surface = self.instance.create_surface(window.clone()).ok()?;
+        let surface_caps = surface.get_capabilities(&self.adapter);
+        if !surface_caps.formats.contains(&self.config.format) {
+            return None;
+        }
+        let config = wgpu::SurfaceConfiguration {
+            width: size.width.max(1),
+            height: size.height.max(1),
+            alpha_mode: surface_caps.alpha_modes[0],
+            ..self.config.clone()
+        };
+        surface.configure(&self.device, &config);
+
+        Some(WindowSurface {
+            window,
+            surface,
+            config
+        })
+    }
+
     pub(crate) fn request_redraw(&self) {
         self.window.request_redraw();
     }
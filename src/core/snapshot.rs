@@ -0,0 +1,180 @@
+
+use std::{fmt::Display, path::{Path, PathBuf}};
+
+use crate::{Headless, Vec2, UI};
+
+/// Set this environment variable to overwrite stored snapshots with the newly rendered images
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "PIERRO_UPDATE_SNAPSHOTS";
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The stored snapshot could not be read, or the new images could not be written
+    Image(image::ImageError),
+    /// There is no stored snapshot yet. The rendered image was written to `new_path`.
+    Missing {
+        path: PathBuf,
+        new_path: PathBuf
+    },
+    /// The rendered image has a different size than the stored snapshot
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+        new_path: PathBuf
+    },
+    /// Too many pixels differ from the stored snapshot.
+    /// The rendered image was written to `new_path`, and the differing pixels are highlighted in the image at `diff_path`.
+    Mismatch {
+        differing_pixels: usize,
+        new_path: PathBuf,
+        diff_path: PathBuf
+    }
+}
+
+impl Display for SnapshotError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Image(err) => write!(f, "snapshot image error: {}", err),
+            SnapshotError::Missing { path, new_path } =>
+                write!(f, "snapshot {} does not exist, new image written to {}", path.display(), new_path.display()),
+            SnapshotError::SizeMismatch { expected, actual, new_path } =>
+                write!(f, "snapshot size mismatch: expected {}x{}, got {}x{}, new image written to {}", expected.0, expected.1, actual.0, actual.1, new_path.display()),
+            SnapshotError::Mismatch { differing_pixels, new_path, diff_path } =>
+                write!(f, "{} pixels differ from snapshot, new image written to {}, diff written to {}", differing_pixels, new_path.display(), diff_path.display()),
+        }
+    }
+
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<image::ImageError> for SnapshotError {
+
+    fn from(err: image::ImageError) -> Self {
+        Self::Image(err)
+    }
+
+}
+
+/// Renders a piece of UI headlessly and compares it against an image stored on disk.
+/// Useful for catching unintended visual changes to widgets.
+pub struct Snapshot {
+    window_size: Vec2,
+    scale_factor: f32,
+    frames: u32,
+    tolerance: u8,
+    max_differing_pixels: usize
+}
+
+impl Snapshot {
+
+    pub fn new(window_size: Vec2) -> Self {
+        Self {
+            window_size,
+            scale_factor: 1.0,
+            frames: 2,
+            tolerance: 2,
+            max_differing_pixels: 0
+        }
+    }
+
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Set the number of frames the UI runs for before being rendered.
+    /// Some widgets need a few frames to settle, since they depend on the layout of previous frames.
+    pub fn with_frames(mut self, frames: u32) -> Self {
+        self.frames = frames.max(1);
+        self
+    }
+
+    /// Set the largest difference allowed in any color channel before a pixel counts as different
+    pub fn with_tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the number of pixels that may differ before the comparison fails
+    pub fn with_max_differing_pixels(mut self, max_differing_pixels: usize) -> Self {
+        self.max_differing_pixels = max_differing_pixels;
+        self
+    }
+
+    /// Render the UI produced by `body` into an image
+    pub fn render<F: FnMut(&mut UI)>(&self, mut body: F) -> image::RgbaImage {
+        let mut headless = Headless::new(self.window_size).with_scale_factor(self.scale_factor);
+        for _ in 0..self.frames {
+            headless.tick(&mut body);
+        }
+        headless.render_image()
+    }
+
+    fn pixels_differ(&self, a: &image::Rgba<u8>, b: &image::Rgba<u8>) -> bool {
+        a.0.iter().zip(b.0.iter()).any(|(a, b)| a.abs_diff(*b) > self.tolerance)
+    }
+
+    /// Render the UI produced by `body` and compare it to the image stored at `path`.
+    /// If the `PIERRO_UPDATE_SNAPSHOTS` environment variable is set, the stored image is overwritten instead.
+    pub fn compare<P: AsRef<Path>, F: FnMut(&mut UI)>(&self, path: P, body: F) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        let new_path = path.with_extension("new.png");
+        let diff_path = path.with_extension("diff.png");
+
+        let actual = self.render(body);
+
+        if std::env::var_os(UPDATE_SNAPSHOTS_ENV_VAR).is_some() {
+            actual.save(path)?;
+            return Ok(());
+        }
+
+        if !path.exists() {
+            actual.save(&new_path)?;
+            return Err(SnapshotError::Missing { path: path.to_owned(), new_path });
+        }
+
+        let expected = image::open(path)?.into_rgba8();
+        if expected.dimensions() != actual.dimensions() {
+            actual.save(&new_path)?;
+            return Err(SnapshotError::SizeMismatch { expected: expected.dimensions(), actual: actual.dimensions(), new_path });
+        }
+
+        // Differing pixels are drawn in red on top of a faded copy of the expected image
+        let mut diff = image::RgbaImage::new(expected.width(), expected.height());
+        let mut differing_pixels = 0;
+        for ((expected_pixel, actual_pixel), diff_pixel) in expected.pixels().zip(actual.pixels()).zip(diff.pixels_mut()) {
+            if self.pixels_differ(expected_pixel, actual_pixel) {
+                differing_pixels += 1;
+                *diff_pixel = image::Rgba([255, 0, 0, 255]);
+            } else {
+                let [r, g, b, _] = expected_pixel.0;
+                let luma = ((r as u32 + g as u32 + b as u32) / 3) as u8;
+                *diff_pixel = image::Rgba([luma, luma, luma, 64]);
+            }
+        }
+
+        if differing_pixels > self.max_differing_pixels {
+            actual.save(&new_path)?;
+            diff.save(&diff_path)?;
+            return Err(SnapshotError::Mismatch { differing_pixels, new_path, diff_path });
+        }
+
+        // Clean up images left over from previous failures
+        let _ = std::fs::remove_file(&new_path);
+        let _ = std::fs::remove_file(&diff_path);
+
+        Ok(())
+    }
+
+    /// Like `compare`, but panics if the comparison fails. Meant to be used in tests.
+    pub fn assert<P: AsRef<Path>, F: FnMut(&mut UI)>(&self, path: P, body: F) {
+        if let Err(err) = self.compare(path, body) {
+            panic!("{}", err);
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests;
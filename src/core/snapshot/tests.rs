@@ -0,0 +1,174 @@
+
+use std::{path::{Path, PathBuf}, sync::{Mutex, MutexGuard}};
+
+use crate::{checkbox, vec2, Color, Size, UINodeParams, UI};
+
+use super::{Snapshot, SnapshotError, UPDATE_SNAPSHOTS_ENV_VAR};
+
+/// Comparisons read an environment variable that one of the tests sets, so the tests can't run at the same time
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_env() -> MutexGuard<'static, ()> {
+    ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// A directory for the images written by a test, removed once the test is done
+struct TempDir(PathBuf);
+
+impl TempDir {
+
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("pierro_snapshot_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+
+}
+
+impl Drop for TempDir {
+
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+
+}
+
+fn square(color: Color) -> impl FnMut(&mut UI) {
+    move |ui| {
+        ui.node(UINodeParams::new(Size::px(10.0), Size::px(10.0)).with_fill(color));
+    }
+}
+
+const RED: Color = Color::rgb(1.0, 0.0, 0.0);
+const BLUE: Color = Color::rgb(0.0, 0.0, 1.0);
+
+fn snapshot() -> Snapshot {
+    Snapshot::new(vec2(20.0, 20.0))
+}
+
+/// Store an image rendered by `body` at `path`
+fn store<F: FnMut(&mut UI)>(snapshot: &Snapshot, path: &Path, body: F) -> image::RgbaImage {
+    let image = snapshot.render(body);
+    image.save(path).unwrap();
+    image
+}
+
+#[test]
+fn missing_snapshot() {
+    let _lock = lock_env();
+    let dir = TempDir::new("missing");
+    let path = dir.file("square.png");
+
+    match snapshot().compare(&path, square(RED)) {
+        Err(SnapshotError::Missing { new_path, .. }) => {
+            assert_eq!(new_path, dir.file("square.new.png"));
+            assert!(new_path.exists());
+        },
+        other => panic!("expected a missing snapshot, got {:?}", other)
+    }
+    assert!(!path.exists());
+}
+
+#[test]
+fn matching_snapshot_cleans_up_old_images() {
+    let _lock = lock_env();
+    let dir = TempDir::new("matching");
+    let path = dir.file("square.png");
+    store(&snapshot(), &path, square(RED));
+    std::fs::write(dir.file("square.new.png"), []).unwrap();
+    std::fs::write(dir.file("square.diff.png"), []).unwrap();
+
+    snapshot().compare(&path, square(RED)).unwrap();
+    assert!(!dir.file("square.new.png").exists());
+    assert!(!dir.file("square.diff.png").exists());
+}
+
+#[test]
+fn mismatching_snapshot_writes_new_and_diff_images() {
+    let _lock = lock_env();
+    let dir = TempDir::new("mismatching");
+    let path = dir.file("square.png");
+    store(&snapshot(), &path, square(RED));
+
+    match snapshot().compare(&path, square(BLUE)) {
+        Err(SnapshotError::Mismatch { differing_pixels, new_path, diff_path }) => {
+            assert_eq!(differing_pixels, 100);
+            assert_eq!(image::open(new_path).unwrap().into_rgba8(), snapshot().render(square(BLUE)));
+            let diff = image::open(diff_path).unwrap().into_rgba8();
+            assert_eq!(diff.get_pixel(5, 5).0, [255, 0, 0, 255]);
+            assert_eq!(diff.get_pixel(15, 15).0[3], 64);
+        },
+        other => panic!("expected a mismatch, got {:?}", other)
+    }
+}
+
+#[test]
+fn tolerance_and_max_differing_pixels() {
+    let _lock = lock_env();
+    let dir = TempDir::new("tolerance");
+    let path = dir.file("square.png");
+    let mut image = snapshot().render(square(RED));
+
+    // Within the tolerance of 2
+    let pixel = image.get_pixel_mut(15, 15);
+    pixel.0[1] = pixel.0[1].wrapping_add(2);
+    image.save(&path).unwrap();
+    snapshot().compare(&path, square(RED)).unwrap();
+
+    // Beyond the tolerance, but within the number of pixels allowed to differ
+    let pixel = image.get_pixel_mut(15, 15);
+    pixel.0[1] = pixel.0[1].wrapping_add(1);
+    image.save(&path).unwrap();
+    assert!(matches!(snapshot().compare(&path, square(RED)), Err(SnapshotError::Mismatch { differing_pixels: 1, .. })));
+    snapshot().with_tolerance(3).compare(&path, square(RED)).unwrap();
+    snapshot().with_max_differing_pixels(1).compare(&path, square(RED)).unwrap();
+}
+
+#[test]
+fn size_mismatch() {
+    let _lock = lock_env();
+    let dir = TempDir::new("size");
+    let path = dir.file("square.png");
+    store(&Snapshot::new(vec2(30.0, 20.0)), &path, square(RED));
+
+    match snapshot().compare(&path, square(RED)) {
+        Err(SnapshotError::SizeMismatch { expected, actual, new_path }) => {
+            assert_eq!(expected, (30, 20));
+            assert_eq!(actual, (20, 20));
+            assert!(new_path.exists());
+        },
+        other => panic!("expected a size mismatch, got {:?}", other)
+    }
+}
+
+#[test]
+fn update_snapshots() {
+    let _lock = lock_env();
+    let dir = TempDir::new("update");
+    let path = dir.file("square.png");
+    store(&snapshot(), &path, square(RED));
+
+    std::env::set_var(UPDATE_SNAPSHOTS_ENV_VAR, "1");
+    let result = snapshot().compare(&path, square(BLUE));
+    std::env::remove_var(UPDATE_SNAPSHOTS_ENV_VAR);
+    result.unwrap();
+
+    assert_eq!(image::open(&path).unwrap().into_rgba8(), snapshot().render(square(BLUE)));
+    assert!(!dir.file("square.new.png").exists());
+    snapshot().compare(&path, square(BLUE)).unwrap();
+}
+
+#[test]
+fn checkbox_snapshot() {
+    let _lock = lock_env();
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/core/snapshot/snapshots/checkbox.png");
+    Snapshot::new(vec2(30.0, 50.0)).assert(path, |ui| {
+        checkbox(ui, &mut true);
+        checkbox(ui, &mut false);
+    });
+}
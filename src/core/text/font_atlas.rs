@@ -3,46 +3,53 @@ use std::collections::HashMap;
 use cosmic_text::{CacheKey, FontSystem, SwashCache, SwashContent};
 use etagere::{BucketedAtlasAllocator, size2};
 
-use crate::{vec2, Texture, Vec2};
+use crate::{vec2, Vec2};
 
-struct AtlasTexture {
-    texture: Texture,
-    packer: BucketedAtlasAllocator,
-    size: u32
+/// The part of an atlas page that was modified since it was last uploaded to the GPU
+#[derive(Clone, Copy)]
+pub(crate) struct DirtyRegion {
+    pub(crate) min_x: u32,
+    pub(crate) min_y: u32,
+    pub(crate) max_x: u32,
+    pub(crate) max_y: u32
 }
 
-impl AtlasTexture {
-
-    const SIZE: u32 = 1024;
+impl DirtyRegion {
 
-    pub fn new(device: &wgpu::Device) -> Self {
-
-        let size = Self::SIZE.min(device.limits().max_texture_dimension_2d);
+    fn union(self, other: DirtyRegion) -> Self {
+        Self {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y)
+        }
+    }
 
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("pierro_font_atlas_texture"),
-            size: wgpu::Extent3d { width: size, height: size, depth_or_array_layers: 1 },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
+}
 
-        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+/// A single texture page of the font atlas, stored on the CPU
+pub(crate) struct AtlasPage {
+    pub(crate) image: image::RgbaImage,
+    packer: BucketedAtlasAllocator,
+    pub(crate) dirty: Option<DirtyRegion>
+}
 
-        let packer = BucketedAtlasAllocator::new(size2(size as i32, size as i32));
+impl AtlasPage {
 
-        let texture = Texture::new(texture, texture_view);
+    pub(crate) const SIZE: u32 = 1024;
 
+    fn new() -> Self {
         Self {
-            texture,
-            packer,
-            size
+            image: image::RgbaImage::new(Self::SIZE, Self::SIZE),
+            packer: BucketedAtlasAllocator::new(size2(Self::SIZE as i32, Self::SIZE as i32)),
+            dirty: None
         }
     }
 
+    pub(crate) fn size(&self) -> u32 {
+        Self::SIZE
+    }
+
 }
 
 #[derive(Clone, Copy)]
@@ -55,27 +62,27 @@ pub(crate) struct GlyphData {
 }
 
 struct GlyphLookup {
-    texture_id: usize,
+    page: usize,
     data: GlyphData
 }
 
 pub(crate) struct FontAtlas {
-    textures: Vec<AtlasTexture>,
+    pub(crate) pages: Vec<AtlasPage>,
     glyph_lookup: HashMap<CacheKey, GlyphLookup>
 }
 
-pub(crate) struct Glyph<'a> {
-    pub(crate) texture: &'a Texture,
+pub(crate) struct Glyph {
+    pub(crate) page: usize,
     pub(crate) data: GlyphData
 }
 
 impl FontAtlas {
 
-    pub(crate) fn new(device: &wgpu::Device) -> Self {
-        
+    pub(crate) fn new() -> Self {
+
         Self {
-            textures: vec![AtlasTexture::new(device)],
-            glyph_lookup: HashMap::new() 
+            pages: vec![AtlasPage::new()],
+            glyph_lookup: HashMap::new()
         }
     }
 
@@ -84,14 +91,11 @@ impl FontAtlas {
         glyph: CacheKey,
 
         font_system: &mut FontSystem,
-        swash_cache: &mut SwashCache,
-
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
+        swash_cache: &mut SwashCache
     ) -> Option<Glyph> {
         if let Some(lookup) = self.glyph_lookup.get(&glyph) {
             return Some(Glyph {
-                texture: &self.textures[lookup.texture_id].texture,
+                page: lookup.page,
                 data: lookup.data
             });
         }
@@ -117,43 +121,42 @@ impl FontAtlas {
             SwashContent::SubpixelMask => panic!("subpixel text antialiasing not be supported."),
         };
 
-        let alloc = if let Some(alloc) = self.textures.last_mut()?.packer.allocate(size2(w as i32, h as i32)) {
+        let alloc = if let Some(alloc) = self.pages.last_mut()?.packer.allocate(size2(w as i32, h as i32)) {
             alloc
         } else {
-            let mut new_texture = AtlasTexture::new(device);
-            // if we can't allocate the glyph in a new texture, we might as well give up.
-            let alloc = new_texture.packer.allocate(size2(w as i32, h as i32))?;
-            self.textures.push(new_texture);
+            let mut new_page = AtlasPage::new();
+            // if we can't allocate the glyph in a new page, we might as well give up.
+            let alloc = new_page.packer.allocate(size2(w as i32, h as i32))?;
+            self.pages.push(new_page);
             alloc
         };
 
         let alloc = alloc.rectangle;
+        let min_x = alloc.min.x as u32;
+        let min_y = alloc.min.y as u32;
+
+        let page_idx = self.pages.len() - 1;
+        let page = self.pages.last_mut()?;
+        for row in 0..h {
+            for col in 0..w {
+                let idx = ((row * w + col) * 4) as usize;
+                let pixel = image::Rgba([data[idx], data[idx + 1], data[idx + 2], data[idx + 3]]);
+                page.image.put_pixel(min_x + col, min_y + row, pixel);
+            }
+        }
 
-        let texture = self.textures.last()?;
-        queue.write_texture(
-            wgpu::ImageCopyTextureBase {
-                texture: &texture.texture.texture(),
-                mip_level: 0,
-                origin: wgpu::Origin3d { x: alloc.min.x as u32, y: alloc.min.y as u32, z: 0 },
-                aspect: wgpu::TextureAspect::All 
-            },
-            &data,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * w),
-                rows_per_image: None
-            },
-            wgpu::Extent3d {
-                width: w,
-                height: h,
-                depth_or_array_layers: 1 
-            } 
-        );
+        let region = DirtyRegion {
+            min_x,
+            min_y,
+            max_x: min_x + w,
+            max_y: min_y + h
+        };
+        page.dirty = Some(page.dirty.map(|dirty| dirty.union(region)).unwrap_or(region));
 
         let pos = vec2(x as f32, -y as f32);
         let size = vec2(w as f32, h as f32);
-        let uv_min = vec2(alloc.min.x as f32, alloc.min.y as f32) / Vec2::splat(texture.size as f32);
-        let uv_max = vec2((alloc.min.x as u32 + w) as f32, (alloc.min.y as u32 + h) as f32) / Vec2::splat(texture.size as f32);
+        let uv_min = vec2(min_x as f32, min_y as f32) / Vec2::splat(page.size() as f32);
+        let uv_max = vec2((min_x + w) as f32, (min_y + h) as f32) / Vec2::splat(page.size() as f32);
 
         let data = GlyphData {
             pos,
@@ -164,14 +167,14 @@ impl FontAtlas {
         };
 
         self.glyph_lookup.insert(glyph, GlyphLookup {
-            texture_id: self.textures.len() - 1,
+            page: page_idx,
             data
         });
 
         Some(Glyph {
-            texture: &texture.texture,
+            page: page_idx,
             data
-        }) 
+        })
     }
 
 }
@@ -3,8 +3,8 @@ mod font_atlas;
 
 use std::collections::HashMap;
 
-use cosmic_text::{fontdb, CacheKey, FontSystem, SwashCache};
-use font_atlas::{FontAtlas, Glyph};
+use cosmic_text::{fontdb, FontSystem, SwashCache};
+pub(crate) use font_atlas::*;
 
 pub(crate) struct Font {
     pub(crate) font_system: FontSystem,
@@ -44,12 +44,12 @@ pub(crate) struct TextResources {
     pub(crate) swash_cache: SwashCache,
     pub(crate) text_font: FontId,
     pub(crate) icon_font: FontId,
-    atlas: FontAtlas
+    pub(crate) atlas: FontAtlas
 }
 
 impl TextResources {
 
-    pub(crate) fn new(device: &wgpu::Device) -> Self {
+    pub(crate) fn new() -> Self {
 
         let text_font = FontId(0); 
         let icon_font = FontId(1);
@@ -62,13 +62,8 @@ impl TextResources {
             swash_cache: SwashCache::new(),
             text_font,
             icon_font,
-            atlas: FontAtlas::new(device),
+            atlas: FontAtlas::new()
         } 
     }
 
-    pub(crate) fn get_glyph(&mut self, font_id: FontId, glyph: CacheKey, device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Glyph> {
-        let font = self.fonts.get_mut(&font_id)?;
-        self.atlas.get_glyph(glyph, &mut font.font_system, &mut self.swash_cache, device, queue)
-    }
-
 }
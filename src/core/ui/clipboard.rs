@@ -1,7 +1,7 @@
 
 use super::UI;
 
-impl UI<'_, '_> {
+impl UI<'_> {
 
     pub fn get_clipboard_text(&mut self) -> Option<String> {
         self.clipboard.as_mut().map(|clipboard| clipboard.get_text().ok()).flatten()
@@ -1,5 +1,6 @@
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LogicalKey {
     Alt,
     CapsLock,
@@ -34,7 +35,40 @@ pub enum LogicalKey {
     F9,
     F10,
     F11,
-    F12
+    F12,
+
+    PageUp,
+    PageDown,
+    Insert,
+    NumLock,
+
+    MediaPlayPause,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    AudioVolumeUp,
+    AudioVolumeDown,
+    AudioVolumeMute
+}
+
+impl LogicalKey {
+
+    /// Every logical key, in declaration order
+    pub const ALL: &[Self] = &[
+        Self::Alt, Self::CapsLock, Self::Control, Self::Fn, Self::Shift, Self::Command,
+        Self::Enter, Self::Tab, Self::Space,
+        Self::ArrowDown, Self::ArrowLeft, Self::ArrowRight, Self::ArrowUp,
+        Self::Backspace, Self::Delete, Self::Escape, Self::Home, Self::End,
+        Self::F1, Self::F2, Self::F3, Self::F4, Self::F5, Self::F6, Self::F7, Self::F8, Self::F9, Self::F10, Self::F11, Self::F12,
+        Self::PageUp, Self::PageDown, Self::Insert, Self::NumLock,
+        Self::MediaPlayPause, Self::MediaStop, Self::MediaTrackNext, Self::MediaTrackPrevious, Self::AudioVolumeUp, Self::AudioVolumeDown, Self::AudioVolumeMute
+    ];
+
+    /// Is this a modifier key, like Shift or Control?
+    pub fn is_modifier(&self) -> bool {
+        matches!(self, Self::Alt | Self::CapsLock | Self::Control | Self::Fn | Self::Shift | Self::Command)
+    }
+
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
@@ -50,6 +84,11 @@ impl Key {
         logical_key: Some(LogicalKey::Shift),
     };
 
+    pub const ALT: Self = Self {
+        text: None,
+        logical_key: Some(LogicalKey::Alt),
+    };
+
     pub const COMMAND: Self = Self {
         text: None,
         #[cfg(target_os = "macos")]
@@ -59,3 +98,234 @@ impl Key {
     };
 
 }
+
+/// The physical location of a key on the keyboard, named after the key in that spot on a US QWERTY layout.
+/// Unlike `LogicalKey` and text, it does not depend on the keyboard layout, which makes it useful for shortcuts like WASD.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyCode {
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+
+    Minus,
+    Equal,
+    BracketLeft,
+    BracketRight,
+    Backslash,
+    Semicolon,
+    Quote,
+    Backquote,
+    Comma,
+    Period,
+    Slash,
+
+    AltLeft,
+    AltRight,
+    ControlLeft,
+    ControlRight,
+    ShiftLeft,
+    ShiftRight,
+    /// The Windows key, or Cmd on macOS
+    SuperLeft,
+    SuperRight,
+    CapsLock,
+    Fn,
+
+    Enter,
+    Tab,
+    Space,
+    Backspace,
+    Delete,
+    Escape,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+
+    NumLock,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadSubtract,
+    NumpadMultiply,
+    NumpadDivide,
+    NumpadDecimal,
+    NumpadEnter,
+
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+
+    MediaPlayPause,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    AudioVolumeUp,
+    AudioVolumeDown,
+    AudioVolumeMute
+}
+
+impl KeyCode {
+
+    /// Every key code, in declaration order
+    pub const ALL: &[Self] = &[
+        Self::KeyA, Self::KeyB, Self::KeyC, Self::KeyD, Self::KeyE, Self::KeyF, Self::KeyG, Self::KeyH, Self::KeyI, Self::KeyJ, Self::KeyK, Self::KeyL, Self::KeyM,
+        Self::KeyN, Self::KeyO, Self::KeyP, Self::KeyQ, Self::KeyR, Self::KeyS, Self::KeyT, Self::KeyU, Self::KeyV, Self::KeyW, Self::KeyX, Self::KeyY, Self::KeyZ,
+        Self::Digit0, Self::Digit1, Self::Digit2, Self::Digit3, Self::Digit4, Self::Digit5, Self::Digit6, Self::Digit7, Self::Digit8, Self::Digit9,
+        Self::Minus, Self::Equal, Self::BracketLeft, Self::BracketRight, Self::Backslash, Self::Semicolon, Self::Quote, Self::Backquote, Self::Comma, Self::Period, Self::Slash,
+        Self::AltLeft, Self::AltRight, Self::ControlLeft, Self::ControlRight, Self::ShiftLeft, Self::ShiftRight, Self::SuperLeft, Self::SuperRight, Self::CapsLock, Self::Fn,
+        Self::Enter, Self::Tab, Self::Space, Self::Backspace, Self::Delete, Self::Escape, Self::Insert, Self::Home, Self::End, Self::PageUp, Self::PageDown,
+        Self::ArrowDown, Self::ArrowLeft, Self::ArrowRight, Self::ArrowUp,
+        Self::NumLock,
+        Self::Numpad0, Self::Numpad1, Self::Numpad2, Self::Numpad3, Self::Numpad4, Self::Numpad5, Self::Numpad6, Self::Numpad7, Self::Numpad8, Self::Numpad9,
+        Self::NumpadAdd, Self::NumpadSubtract, Self::NumpadMultiply, Self::NumpadDivide, Self::NumpadDecimal, Self::NumpadEnter,
+        Self::F1, Self::F2, Self::F3, Self::F4, Self::F5, Self::F6, Self::F7, Self::F8, Self::F9, Self::F10, Self::F11, Self::F12,
+        Self::MediaPlayPause, Self::MediaStop, Self::MediaTrackNext, Self::MediaTrackPrevious, Self::AudioVolumeUp, Self::AudioVolumeDown, Self::AudioVolumeMute
+    ];
+
+}
+
+/// Which modifier keys are held down, on each side of the keyboard.
+/// When the platform does not report which side a modifier is on, it is treated as the left one.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub struct Modifiers {
+    pub left_ctrl: bool,
+    pub right_ctrl: bool,
+    pub left_shift: bool,
+    pub right_shift: bool,
+    pub left_alt: bool,
+    pub right_alt: bool,
+    /// The left Windows key, or Cmd on macOS
+    pub left_logo: bool,
+    /// The right Windows key, or Cmd on macOS
+    pub right_logo: bool
+}
+
+impl Modifiers {
+
+    pub const NONE: Self = Self {
+        left_ctrl: false,
+        right_ctrl: false,
+        left_shift: false,
+        right_shift: false,
+        left_alt: false,
+        right_alt: false,
+        left_logo: false,
+        right_logo: false
+    };
+
+    pub fn ctrl(&self) -> bool {
+        self.left_ctrl || self.right_ctrl
+    }
+
+    pub fn shift(&self) -> bool {
+        self.left_shift || self.right_shift
+    }
+
+    /// Is Alt held down? Option on macOS.
+    pub fn alt(&self) -> bool {
+        self.left_alt || self.right_alt
+    }
+
+    pub fn logo(&self) -> bool {
+        self.left_logo || self.right_logo
+    }
+
+    /// Is the platform's shortcut modifier held down? Cmd on macOS, Ctrl elsewhere.
+    pub fn command(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.logo()
+        } else {
+            self.ctrl()
+        }
+    }
+
+    /// Are no modifiers held down?
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    /// Press or release the left-hand modifier matching a logical key.
+    /// Returns false if the key is not a modifier tracked here.
+    pub fn set_logical_key(&mut self, logical_key: LogicalKey, down: bool) -> bool {
+        match logical_key {
+            LogicalKey::Control => self.left_ctrl = down,
+            LogicalKey::Shift => self.left_shift = down,
+            LogicalKey::Alt => self.left_alt = down,
+            LogicalKey::Command => self.left_logo = down,
+            _ => return false
+        }
+        true
+    }
+
+}
+
+/// A key being pressed or released
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct KeyEvent {
+    /// What the key means in the current keyboard layout
+    pub key: Key,
+    /// Where the key is on the keyboard. `None` if unknown.
+    pub physical_key: Option<KeyCode>,
+    /// Was the key pressed, rather than released?
+    pub pressed: bool,
+    /// The modifiers held down when the event happened
+    pub modifiers: Modifiers
+}
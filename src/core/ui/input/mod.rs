@@ -121,43 +121,57 @@ impl ButtonInput {
 
 }
 
-/// The raw input given to the application by the windowing library
-pub(crate) struct RawInput {
+/// The raw input given to the application by the windowing library.
+/// Can also be filled in by hand to drive the UI without a window.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RawInput {
 
     /// The amount of time elapsed since the last redraw
-    pub(crate) delta_time: f32,
+    pub delta_time: f32,
 
     /// Mouse position in physical pixels. None if the mouse left the window
-    pub(crate) mouse_pos: Option<Vec2>,
+    pub mouse_pos: Option<Vec2>,
     /// Is the left mouse button currently down?
-    pub(crate) l_mouse_down: bool,
+    pub l_mouse_down: bool,
     /// Is the right mouse button currently down?
-    pub(crate) r_mouse_down: bool,
+    pub r_mouse_down: bool,
+    /// Is the middle mouse button currently down?
+    pub m_mouse_down: bool,
+    /// Is the back mouse button currently down?
+    pub back_mouse_down: bool,
+    /// Is the forward mouse button currently down?
+    pub forward_mouse_down: bool,
+    /// The pen or finger touching the screen, if any, with its position in physical pixels
+    pub pen: Option<PenInput>,
     /// How much has the mouse scrolled
-    pub(crate) scroll: Vec2,
+    pub scroll: Vec2,
 
-    /// What keys were pressed this frame?
-    pub(crate) keys_pressed: Vec<Key>,
-    /// What keys were released this frame?
-    pub(crate) keys_released: Vec<Key>,
+    /// The keys pressed and released this frame, in order
+    pub key_events: Vec<KeyEvent>,
+    /// Which modifier keys are currently held down
+    pub modifiers: Modifiers,
 
     /// What is the current IME preedit?
-    pub(crate) ime_preedit: String,
+    pub ime_preedit: String,
     /// What IME text input was commited this frame?
-    pub(crate) ime_commit: Option<String>
+    pub ime_commit: Option<String>
 }
 
 impl RawInput {
 
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             delta_time: 0.0,
             mouse_pos: None,
             l_mouse_down: false,
             r_mouse_down: false,
+            m_mouse_down: false,
+            back_mouse_down: false,
+            forward_mouse_down: false,
+            pen: None,
             scroll: Vec2::ZERO,
-            keys_pressed: Vec::new(),
-            keys_released: Vec::new(),
+            key_events: Vec::new(),
+            modifiers: Modifiers::NONE,
             ime_preedit: String::new(),
             ime_commit: None
         }
@@ -165,6 +179,26 @@ impl RawInput {
 
 }
 
+impl Default for RawInput {
+
+    fn default() -> Self {
+        Self::new()
+    }
+
+}
+
+/// A pen touching the screen. Touches that don't report calibrated pressure, e.g. from fingers, are not pen input,
+/// and only the first of several pens touching the screen at once is tracked.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PenInput {
+    pub position: Vec2,
+    /// How hard the pen is pressed, from 0 to 1
+    pub pressure: f32,
+    /// The angle between the pen and the screen in radians, from 0 when lying flat to π/2 when upright.
+    /// `None` if the platform does not report the pen's tilt.
+    pub altitude: Option<f32>
+}
+
 /// The state of a mouse button
 #[derive(Clone, Copy)]
 pub struct MouseButton {
@@ -246,14 +280,27 @@ pub struct Input {
     pub mouse_pos: Option<Vec2>,
     pub l_mouse: MouseButton,
     pub r_mouse: MouseButton,
+    pub m_mouse: MouseButton,
+    pub back_mouse: MouseButton,
+    pub forward_mouse: MouseButton,
     pub scroll: Vec2,
+    /// The pen or finger touching the screen, if any
+    pub pen: Option<PenInput>,
 
     keys: HashMap<Key, ButtonInput>,
+    physical_keys: HashMap<KeyCode, ButtonInput>,
     pub keys_pressed: Vec<Key>,
     pub keys_released: Vec<Key>,
+    /// The keys pressed and released this frame, in order, along with their physical location and modifiers
+    pub key_events: Vec<KeyEvent>,
+    /// Which modifier keys are held down
+    pub modifiers: Modifiers,
 
     pub ime_preedit: String,
-    pub ime_commit: Option<String>
+    pub ime_commit: Option<String>,
+
+    /// The time elapsed since the last frame, in seconds
+    pub delta_time: f32
 }
 
 /// The memory storing what inputs are provided to a node
@@ -262,6 +309,9 @@ pub(crate) struct Interaction {
     pub(crate) through_hovered: bool,
     pub(crate) l_mouse: MouseButton,
     pub(crate) r_mouse: MouseButton,
+    pub(crate) m_mouse: MouseButton,
+    pub(crate) back_mouse: MouseButton,
+    pub(crate) forward_mouse: MouseButton,
     pub(crate) scroll: Vec2
 }
 
@@ -273,6 +323,9 @@ impl Default for Interaction {
             through_hovered: false,
             l_mouse: MouseButton::new(),
             r_mouse: MouseButton::new(),
+            m_mouse: MouseButton::new(),
+            back_mouse: MouseButton::new(),
+            forward_mouse: MouseButton::new(),
             scroll: Vec2::ZERO
         }
     }
@@ -280,26 +333,34 @@ impl Default for Interaction {
 }
 
 fn find_interacted_node<F: Fn(&mut LayoutMemory) -> bool>(memory: &mut Memory, node: Id, pos: Vec2, ignore: Option<Id>, criteria: &F) -> Option<Id> {
-    // Check priority nodes first
+    let mut children = Vec::new();
     let mut child = memory.get::<LayoutMemory>(node).first_child;
     while let Some(child_id) = child {
-        if memory.get::<LayoutMemory>(child_id).has_interaction_priority {
-            if let Some(node) = find_interacted_node(memory, child_id, pos, ignore, criteria) {
+        children.push(child_id);
+        child = memory.get::<LayoutMemory>(child_id).next;
+    }
+
+    // Children that overlap their siblings are drawn above them, so they are checked first, topmost first
+    let (mut overlapping, children): (Vec<Id>, Vec<Id>) = children.into_iter().partition(|child| memory.get::<LayoutMemory>(*child).overlaps_siblings);
+    overlapping.reverse();
+    let children: Vec<Id> = overlapping.into_iter().chain(children).collect();
+
+    // Check priority nodes first
+    for child_id in &children {
+        if memory.get::<LayoutMemory>(*child_id).has_interaction_priority {
+            if let Some(node) = find_interacted_node(memory, *child_id, pos, ignore, criteria) {
                 return Some(node);
             }
         }
-        child = memory.get::<LayoutMemory>(child_id).next;
-    } 
+    }
 
     // Then check non-priority nodes
-    let mut child = memory.get::<LayoutMemory>(node).first_child;
-    while let Some(child_id) = child {
-        if !memory.get::<LayoutMemory>(child_id).has_interaction_priority {
-            if let Some(node) = find_interacted_node(memory, child_id, pos, ignore, criteria) {
+    for child_id in &children {
+        if !memory.get::<LayoutMemory>(*child_id).has_interaction_priority {
+            if let Some(node) = find_interacted_node(memory, *child_id, pos, ignore, criteria) {
                 return Some(node);
             }
         }
-        child = memory.get::<LayoutMemory>(child_id).next;
     }
 
     let layout_mem = memory.get::<LayoutMemory>(node);
@@ -311,6 +372,16 @@ fn find_interacted_node<F: Fn(&mut LayoutMemory) -> bool>(memory: &mut Memory, n
 
 }
 
+/// Collect the ids of a node and all its descendants from the last frame
+fn collect_nodes(memory: &mut Memory, node: Id, nodes: &mut ahash::AHashSet<Id>) {
+    nodes.insert(node);
+    let mut child = memory.get::<LayoutMemory>(node).first_child;
+    while let Some(child_id) = child {
+        collect_nodes(memory, child_id, nodes);
+        child = memory.get::<LayoutMemory>(child_id).next;
+    }
+}
+
 /// Find the node hovered at a given position in screen space
 fn find_hover_node(memory: &mut Memory, node: Id, pos: Vec2, ignore: Option<Id>) -> Option<Id> {
     find_interacted_node(memory, node, pos, ignore, &|mem| mem.sense_mouse)
@@ -330,6 +401,11 @@ impl Input {
         mouse_pos - prev_mouse_pos
     }
 
+    /// How hard the pen is pressed, from 0 to 1. 1 when using a mouse, so that pressure-sensitive tools still work.
+    pub fn pressure(&self) -> f32 {
+        self.pen.map(|pen| pen.pressure).unwrap_or(1.0)
+    }
+
     /// Get the state of a key
     pub fn key_state(&self, key: Key) -> ButtonInput {
         self.keys.get(&key).map(|state| *state).unwrap_or(ButtonInput::new())
@@ -358,18 +434,46 @@ impl Input {
         self.keys.get_mut(&key).unwrap()
     }
 
+    /// Get the state of a key by its physical location on the keyboard
+    pub fn physical_key_state(&self, key: KeyCode) -> ButtonInput {
+        self.physical_keys.get(&key).copied().unwrap_or(ButtonInput::new())
+    }
+
+    /// Is the key at a physical location down?
+    pub fn physical_key_down(&self, key: KeyCode) -> bool {
+        self.physical_key_state(key).down()
+    }
+
+    /// Has the key at a physical location just been pressed?
+    pub fn physical_key_pressed(&self, key: KeyCode) -> bool {
+        self.physical_key_state(key).pressed()
+    }
+
+    /// Has the key at a physical location just been released?
+    pub fn physical_key_released(&self, key: KeyCode) -> bool {
+        self.physical_key_state(key).released()
+    }
+
     pub(crate) fn new() -> Self {
         Self {
             prev_mouse_pos: None,
             mouse_pos: None,
             l_mouse: MouseButton::new(),
             r_mouse: MouseButton::new(),
+            m_mouse: MouseButton::new(),
+            back_mouse: MouseButton::new(),
+            forward_mouse: MouseButton::new(),
             scroll: Vec2::ZERO,
+            pen: None,
             keys: HashMap::new(),
+            physical_keys: HashMap::new(),
             keys_pressed: Vec::new(),
             keys_released: Vec::new(),
+            key_events: Vec::new(),
+            modifiers: Modifiers::NONE,
             ime_preedit: String::new(),
-            ime_commit: None
+            ime_commit: None,
+            delta_time: 0.0
         }
     }
 
@@ -378,39 +482,67 @@ impl Input {
     pub(crate) fn update(&mut self, raw_input: &mut RawInput, scale_factor: f32) {
         self.prev_mouse_pos = self.mouse_pos;
         self.mouse_pos = raw_input.mouse_pos.map(|pos| pos / scale_factor);
+        self.delta_time = raw_input.delta_time;
 
         self.l_mouse.update(raw_input.l_mouse_down, self.mouse_pos, raw_input.delta_time);
         self.r_mouse.update(raw_input.r_mouse_down, self.mouse_pos, raw_input.delta_time);
+        self.m_mouse.update(raw_input.m_mouse_down, self.mouse_pos, raw_input.delta_time);
+        self.back_mouse.update(raw_input.back_mouse_down, self.mouse_pos, raw_input.delta_time);
+        self.forward_mouse.update(raw_input.forward_mouse_down, self.mouse_pos, raw_input.delta_time);
+        self.pen = raw_input.pen.map(|pen| PenInput {
+            position: pen.position / scale_factor,
+            ..pen
+        });
 
         // If we start dragging, set the mouse position to the previous mouse position
         // so that the drag starting is registered on the same widget where the mouse began
-        if self.l_mouse.drag_started() || self.r_mouse.drag_started() {
+        if self.l_mouse.drag_started() || self.r_mouse.drag_started() || self.m_mouse.drag_started() {
             self.mouse_pos = self.prev_mouse_pos;
         }
         
         self.scroll = raw_input.scroll / scale_factor;
         raw_input.scroll = Vec2::ZERO;
 
-        self.keys_pressed = std::mem::replace(&mut raw_input.keys_pressed, Vec::new());
-        self.keys_released = std::mem::replace(&mut raw_input.keys_released, Vec::new());
-        for key in self.keys_pressed.clone() {
-            self.key_state_mut(&key).press();
-        }
-        for key in self.keys_released.clone() {
-            self.key_state_mut(&key).release();
+        self.modifiers = raw_input.modifiers;
+        self.key_events = std::mem::take(&mut raw_input.key_events);
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        for event in self.key_events.clone() {
+            // Events for keys with no known meaning only affect the physical key state
+            if event.key.text.is_some() || event.key.logical_key.is_some() {
+                if event.pressed {
+                    self.key_state_mut(&event.key).press();
+                    self.keys_pressed.push(event.key);
+                } else {
+                    self.key_state_mut(&event.key).release();
+                    self.keys_released.push(event.key);
+                }
+            }
+            if let Some(physical_key) = event.physical_key {
+                let state = self.physical_keys.entry(physical_key).or_insert(ButtonInput::new());
+                if event.pressed {
+                    state.press();
+                } else {
+                    state.release();
+                }
+            }
         }
         for (_key, state) in self.keys.iter_mut() {
             state.tick_with_same_state(raw_input.delta_time);
         }
+        for (_key, state) in self.physical_keys.iter_mut() {
+            state.tick_with_same_state(raw_input.delta_time);
+        }
 
         self.ime_preedit = raw_input.ime_preedit.clone();
         self.ime_commit = std::mem::replace(&mut raw_input.ime_commit, None);
 
     }
 
-    /// Distribute the input to nodes, taking foucs into account.
-    pub(crate) fn distribute(&self, memory: &mut Memory) {
-        let layer_ids = memory.layer_ids.clone();
+    /// Distribute the input to the nodes of a window, taking foucs into account.
+    /// Only nodes that were in the window's tree last frame are affected.
+    pub(crate) fn distribute(&self, memory: &mut Memory, root_id: Id) {
+        let layer_ids = memory.layer_ids.get(&root_id).cloned().unwrap_or_default();
         let hovered_node = memory.get_focus().or_else(|| {
             let mouse_pos = self.mouse_pos?;
             for layer in layer_ids.iter().rev() { 
@@ -438,16 +570,31 @@ impl Input {
             None
         }).flatten();
 
+        let mut window_nodes = ahash::AHashSet::new();
+        for layer in &layer_ids {
+            collect_nodes(memory, *layer, &mut window_nodes);
+        }
+
         for (id, interaction) in memory.iter_mut::<Interaction>() {
+            if !window_nodes.contains(&id) {
+                continue;
+            }
             let hovered = Some(id) == hovered_node;
             let scrollable = Some(id) == scrollable_node;
             interaction.hovered = hovered;
             interaction.through_hovered = Some(id) == through_hovered_node;
             interaction.l_mouse = if hovered { self.l_mouse } else { MouseButton::new() };
             interaction.r_mouse = if hovered { self.r_mouse } else { MouseButton::new() };
+            interaction.m_mouse = if hovered { self.m_mouse } else { MouseButton::new() };
+            interaction.back_mouse = if hovered { self.back_mouse } else { MouseButton::new() };
+            interaction.forward_mouse = if hovered { self.forward_mouse } else { MouseButton::new() };
             interaction.scroll = if scrollable { self.scroll } else { Vec2::ZERO };
         }
+    }
 
+    /// Clear the drag and drop payload if the mouse is not being held down.
+    /// Called once per frame with the input of the main window.
+    pub(crate) fn release_dnd_payload(&self, memory: &mut Memory) {
         // If we're not holding the mouse down, we can't be drag and dropping anything
         if !self.l_mouse.down() && !self.l_mouse.released() {
             memory.clear_dnd_payload();
@@ -466,6 +613,9 @@ pub struct Response {
     pub through_hovered: bool,
     pub l_mouse: MouseButton,
     pub r_mouse: MouseButton,
+    pub m_mouse: MouseButton,
+    pub back_mouse: MouseButton,
+    pub forward_mouse: MouseButton,
     pub scroll: Vec2
 }
 
@@ -573,8 +723,56 @@ impl Response {
         ui.input().mouse_delta() / scale
     }
 
+    pub fn middle_mouse_down(&self) -> bool {
+        self.m_mouse.down()
+    }
+
+    pub fn middle_mouse_pressed(&self) -> bool {
+        self.m_mouse.pressed()
+    }
+
+    pub fn middle_mouse_released(&self) -> bool {
+        self.m_mouse.released()
+    }
+
+    pub fn middle_mouse_clicked(&self) -> bool {
+        self.m_mouse.clicked()
+    }
+
+    pub fn middle_mouse_double_clicked(&self) -> bool {
+        self.m_mouse.double_clicked()
+    }
+
+    pub fn middle_dragging(&self) -> bool {
+        self.m_mouse.dragging()
+    }
+
+    pub fn middle_drag_started(&self) -> bool {
+        self.m_mouse.drag_started()
+    }
+
+    pub fn middle_drag_stopped(&self) -> bool {
+        self.m_mouse.drag_stopped()
+    }
+
+    pub fn middle_drag_delta(&self, ui: &mut UI) -> Vec2 {
+        if !self.middle_dragging() {
+            return Vec2::ZERO;
+        }
+        let scale = self.scale(ui);
+        ui.input().mouse_delta() / scale
+    }
+
+    pub fn back_mouse_clicked(&self) -> bool {
+        self.back_mouse.clicked()
+    }
+
+    pub fn forward_mouse_clicked(&self) -> bool {
+        self.forward_mouse.clicked()
+    }
+
     pub fn mouse_pressed_outside(&self, ui: &mut UI) -> bool {
-        (ui.input().l_mouse.pressed() || ui.input().r_mouse.pressed()) && !self.contains_mouse(ui)
+        (ui.input().l_mouse.pressed() || ui.input().r_mouse.pressed() || ui.input().m_mouse.pressed()) && !self.contains_mouse(ui)
     }
 
     pub fn is_focused(&self, ui: &mut UI) -> bool {
@@ -600,3 +798,6 @@ impl Response {
     }
 
 }
+
+#[cfg(test)]
+mod tests;
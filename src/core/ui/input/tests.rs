@@ -0,0 +1,46 @@
+
+use crate::{button, container, stack, vec2, Anchor, Headless, Layout, Response, Size, UINodeParams, Vec2, UI};
+
+fn badge(ui: &mut UI) -> Response {
+    ui.node(UINodeParams::new(Size::px(10.0), Size::px(10.0)).with_anchor(Anchor::top_right()).sense_mouse())
+}
+
+/// Click at `pos`, returning which of the two responses returned by `body` were clicked
+fn click<F: FnMut(&mut UI) -> (Response, Response)>(headless: &mut Headless, pos: Vec2, mut body: F) -> (bool, bool) {
+    headless.tick(&mut body);
+    headless.move_mouse(pos);
+    headless.press_l_mouse();
+    headless.tick(&mut body);
+    headless.release_l_mouse();
+    let (a, b) = headless.tick(&mut body);
+    (a.mouse_clicked(), b.mouse_clicked())
+}
+
+#[test]
+fn anchored_badge_wins_over_button_under_it() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let body = |ui: &mut UI| container(ui, Size::fit(), Size::fit(), Layout::horizontal(), |ui| {
+        let button = button(ui, "Save");
+        (button, badge(ui))
+    }).1;
+    let (button_rect, badge_rect) = {
+        let (button, badge) = headless.tick(body);
+        (headless.node_rect(button.id).unwrap(), headless.node_rect(badge.id).unwrap())
+    };
+    assert!(button_rect.contains(badge_rect.center()));
+
+    assert_eq!(click(&mut headless, badge_rect.center(), body), (false, true));
+    assert_eq!(click(&mut headless, button_rect.bl() + vec2(2.0, -2.0), body), (true, false));
+}
+
+#[test]
+fn topmost_stacked_node_wins() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let body = |ui: &mut UI| stack(ui, |ui| {
+        let below = ui.node(UINodeParams::new(Size::px(50.0), Size::px(50.0)).sense_mouse());
+        let above = ui.node(UINodeParams::new(Size::px(20.0), Size::px(20.0)).sense_mouse());
+        (below, above)
+    }).1;
+    assert_eq!(click(&mut headless, vec2(10.0, 10.0), body), (false, true));
+    assert_eq!(click(&mut headless, vec2(40.0, 40.0), body), (true, false));
+}
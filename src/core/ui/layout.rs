@@ -1,7 +1,9 @@
 
+use std::rc::Rc;
+
 use crate::{text::{FontId, TextResources}, vec2, Axis, PerAxis, Range, Rect, TSTransform, Vec2, AXES};
 
-use super::{Id, Memory, UIRef, UITree};
+use super::{Id, Memory, UINode, UIRef, UITree};
 
 #[derive(Clone, Copy)]
 pub enum SizeKind {
@@ -13,13 +15,18 @@ pub enum SizeKind {
     Fr(f32),
     /// Big enough to fit the children of the node
     Fit,
+    /// Derived from the node's size on the other axis, keeping a ratio of width to height.
+    /// Widths are laid out before heights, so a width can only be derived from a height of a constant number of pixels.
+    AspectRatio(f32)
 }
 
 #[derive(Clone, Copy)]
 pub struct Size {
     size: SizeKind,
     shrink: bool,
-    grow: f32
+    grow: f32,
+    min: f32,
+    max: f32
 }
 
 impl Size {
@@ -28,7 +35,9 @@ impl Size {
         Self {
             size,
             shrink: true,
-            grow: 0.0
+            grow: 0.0,
+            min: 0.0,
+            max: f32::INFINITY
         }
     }
 
@@ -48,6 +57,11 @@ impl Size {
         Self::new(SizeKind::Fit)
     }
 
+    /// Keep a ratio of width to height, e.g. 16/9 for a video preview
+    pub fn aspect_ratio(ratio: f32) -> Self {
+        Self::new(SizeKind::AspectRatio(ratio))
+    }
+
     pub fn no_shrink(mut self) -> Self {
         self.shrink = false;
         self
@@ -58,13 +72,35 @@ impl Size {
         self
     }
 
+    /// Never shrink below a number of pixels
+    pub fn with_min(mut self, min: f32) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// Never grow beyond a number of pixels
+    pub fn with_max(mut self, max: f32) -> Self {
+        self.max = max;
+        self
+    }
+
+    fn clamp(&self, size: f32) -> f32 {
+        size.min(self.max).max(self.min)
+    }
+
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Justify {
     Min,
     Center,
-    Max
+    Max,
+    /// Put the leftover space between the children, with none at the edges
+    SpaceBetween,
+    /// Put the same amount of leftover space on both sides of each child
+    SpaceAround,
+    /// Put the same amount of leftover space between the children and at the edges
+    SpaceEvenly
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -74,12 +110,139 @@ pub enum Align {
     Max
 }
 
+/// The sizes of the columns and rows of a grid layout
+#[derive(Clone)]
+struct GridTracks {
+    columns: Rc<[Size]>,
+    rows: Rc<[Size]>,
+    /// The size of the rows added once the children no longer fit in `rows`
+    auto_row: Size
+}
+
+impl GridTracks {
+
+    fn track(&self, axis: Axis, idx: usize) -> Size {
+        match axis {
+            Axis::X => self.columns.get(idx).copied().unwrap_or(Size::fit()),
+            Axis::Y => self.rows.get(idx).copied().unwrap_or(self.auto_row)
+        }
+    }
+
+}
+
+/// Where a child of a grid layout is placed, set using `UINodeParams::with_grid_cell`
+#[derive(Clone, Copy)]
+pub struct GridCell {
+    /// The column and row of the top left cell the child covers. `None` places the child in the next free cell.
+    position: Option<(usize, usize)>,
+    /// How many columns and rows the child covers
+    span: PerAxis<usize>,
+    /// How the child is aligned within its cell. `None` uses the alignment of the grid.
+    align: PerAxis<Option<Align>>
+}
+
+impl GridCell {
+
+    /// Place the child in the next free cell
+    pub const AUTO: Self = Self {
+        position: None,
+        span: PerAxis::new(1, 1),
+        align: PerAxis::new(None, None)
+    };
+
+    pub fn at(column: usize, row: usize) -> Self {
+        Self {
+            position: Some((column, row)),
+            ..Self::AUTO
+        }
+    }
+
+    pub fn with_span(mut self, columns: usize, rows: usize) -> Self {
+        self.span = PerAxis::new(columns, rows);
+        self
+    }
+
+    pub fn with_horizontal_align(mut self, align: Align) -> Self {
+        self.align.x = Some(align);
+        self
+    }
+
+    pub fn with_vertical_align(mut self, align: Align) -> Self {
+        self.align.y = Some(align);
+        self
+    }
+
+}
+
+impl Default for GridCell {
+
+    fn default() -> Self {
+        Self::AUTO
+    }
+
+}
+
+/// Where a node is placed within its parent when it is taken out of the parent's layout, set using `UINodeParams::with_anchor`.
+/// Anchored nodes do not take up space in their parent, so they can overlap its other children, e.g. for a badge on a button.
 #[derive(Clone, Copy)]
+pub struct Anchor {
+    /// Which side of the parent the node sticks to on each axis
+    align: PerAxis<Align>,
+    /// Added to the node's position once it is anchored
+    offset: Vec2
+}
+
+impl Anchor {
+
+    pub fn new(horizontal: Align, vertical: Align) -> Self {
+        Self {
+            align: PerAxis::new(horizontal, vertical),
+            offset: Vec2::ZERO
+        }
+    }
+
+    pub fn top_left() -> Self {
+        Self::new(Align::Min, Align::Min)
+    }
+
+    pub fn top_right() -> Self {
+        Self::new(Align::Max, Align::Min)
+    }
+
+    pub fn bottom_left() -> Self {
+        Self::new(Align::Min, Align::Max)
+    }
+
+    pub fn bottom_right() -> Self {
+        Self::new(Align::Max, Align::Max)
+    }
+
+    pub fn center() -> Self {
+        Self::new(Align::Center, Align::Center)
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+}
+
+#[derive(Clone)]
 pub struct Layout {
     axis: Axis,
     justify: Justify,
     align: Align,
-    allow_overflow: PerAxis<bool>
+    allow_overflow: PerAxis<bool>,
+    /// Do children that don't fit on the main axis flow onto new lines?
+    wrap: bool,
+    /// The space between children on the main axis
+    main_gap: f32,
+    /// The space between lines of wrapped children
+    cross_gap: f32,
+    grid: Option<GridTracks>,
+    /// Are the children placed on top of each other?
+    stack: bool
 }
 
 impl Layout {
@@ -89,7 +252,38 @@ impl Layout {
             axis,
             justify: Justify::Min,
             align: Align::Min,
-            allow_overflow: PerAxis::splat(false)
+            allow_overflow: PerAxis::splat(false),
+            wrap: false,
+            main_gap: 0.0,
+            cross_gap: 0.0,
+            grid: None,
+            stack: false
+        }
+    }
+
+    /// A grid with a column for each size in `columns`, which children fill row by row.
+    /// Rows are added as needed, fitting their contents unless set with `with_rows` or `with_auto_rows`.
+    /// Fractional columns and rows share the space left by the others, but never shrink below their contents.
+    /// Children are positioned within their cells horizontally by the layout's justify and vertically by its align.
+    /// Justifying with space positions children like `Justify::Min`, since each cell holds a single child.
+    /// The main and cross gaps separate the columns and rows.
+    pub fn grid(columns: Vec<Size>) -> Self {
+        Self {
+            grid: Some(GridTracks {
+                columns: columns.into(),
+                rows: Rc::new([]),
+                auto_row: Size::fit()
+            }),
+            ..Self::horizontal()
+        }
+    }
+
+    /// Children are placed on top of each other, each within the whole node, with later children drawn above earlier ones.
+    /// Like the cells of a grid, children are positioned horizontally by the layout's justify and vertically by its align.
+    pub fn stack() -> Self {
+        Self {
+            stack: true,
+            ..Self::vertical()
         }
     }
 
@@ -118,6 +312,18 @@ impl Layout {
         self.with_justify(Justify::Max)
     }
 
+    pub fn justify_space_between(self) -> Self {
+        self.with_justify(Justify::SpaceBetween)
+    }
+
+    pub fn justify_space_around(self) -> Self {
+        self.with_justify(Justify::SpaceAround)
+    }
+
+    pub fn justify_space_evenly(self) -> Self {
+        self.with_justify(Justify::SpaceEvenly)
+    }
+
     pub fn with_align(mut self, align: Align) -> Self {
         self.align = align;
         self
@@ -145,18 +351,168 @@ impl Layout {
         self
     }
 
+    /// Let children that don't fit on the main axis flow onto new lines, like words in a paragraph.
+    /// Horizontal layouts wrap using their final width. Vertical layouts wrap using their basis height,
+    /// since heights are laid out after widths, and their width does not account for the extra columns.
+    pub fn with_wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    pub fn with_main_gap(mut self, gap: f32) -> Self {
+        self.main_gap = gap;
+        self
+    }
+
+    /// Set the space between lines of wrapped children
+    pub fn with_cross_gap(mut self, gap: f32) -> Self {
+        self.cross_gap = gap;
+        self
+    }
+
+    pub fn with_gap(self, main_gap: f32, cross_gap: f32) -> Self {
+        self.with_main_gap(main_gap).with_cross_gap(cross_gap)
+    }
+
+    /// Set the sizes of the first rows of a grid layout
+    pub fn with_rows(mut self, rows: Vec<Size>) -> Self {
+        if let Some(grid) = &mut self.grid {
+            grid.rows = rows.into();
+        }
+        self
+    }
+
+    /// Set the size of the rows of a grid layout after the ones set with `with_rows`
+    pub fn with_auto_rows(mut self, size: Size) -> Self {
+        if let Some(grid) = &mut self.grid {
+            grid.auto_row = size;
+        }
+        self
+    }
+
+    /// The space between the columns or rows of a grid layout
+    fn grid_gap(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::X => self.main_gap,
+            Axis::Y => self.cross_gap
+        }
+    }
+
+    /// How children are positioned within their cell of a grid or within a stack
+    fn cell_align(&self, axis: Axis) -> Align {
+        match axis {
+            Axis::X => match self.justify {
+                Justify::Center => Align::Center,
+                Justify::Max => Align::Max,
+                Justify::Min | Justify::SpaceBetween | Justify::SpaceAround | Justify::SpaceEvenly => Align::Min
+            },
+            Axis::Y => self.align
+        }
+    }
+
+    /// Are the children of a node with the layout laid out one after another on `axis`?
+    fn flows_on_axis(&self, axis: Axis) -> bool {
+        self.axis == axis && !self.stack
+    }
+
 }
 
 const TINY: f32 = 0.000000000000001;
 
 impl UITree {
 
+    fn children(&self, node: UIRef) -> Vec<UIRef> {
+        let mut children = Vec::new();
+        let mut child_ref = self.get(node).first_child;
+        while child_ref.is_some() {
+            children.push(child_ref);
+            child_ref = self.get(child_ref).next;
+        }
+        children
+    }
+
+    fn is_anchored(&self, node: UIRef) -> bool {
+        self.get(node).params.anchor.is_some()
+    }
+
+    /// The children that take up space in the node's layout
+    fn flow_children(&self, node: UIRef) -> Vec<UIRef> {
+        self.children(node).into_iter().filter(|child| !self.is_anchored(*child)).collect()
+    }
+
+    /// The children placed using an anchor instead of the node's layout
+    fn anchored_children(&self, node: UIRef) -> Vec<UIRef> {
+        self.children(node).into_iter().filter(|child| self.is_anchored(*child)).collect()
+    }
+
+    /// The total size of the gaps between a number of children on the main axis
+    fn main_gaps_size(n_children: usize, layout: &Layout) -> f32 {
+        n_children.saturating_sub(1) as f32 * layout.main_gap
+    }
+
+    /// Split children into lines that fit within `size` on the main axis
+    fn wrap_lines<'a>(&self, children: &'a [UIRef], layout: &Layout, size: f32) -> Vec<&'a [UIRef]> {
+        let mut lines = Vec::new();
+        let mut line_start = 0;
+        let mut line_size = 0.0;
+        for (idx, child) in children.iter().enumerate() {
+            let child_size = self.get(*child).basis_size.on_axis(layout.axis);
+            if idx > line_start && line_size + layout.main_gap + child_size > size + 0.001 {
+                lines.push(&children[line_start..idx]);
+                line_start = idx;
+                line_size = child_size;
+            } else if idx > line_start {
+                line_size += layout.main_gap + child_size;
+            } else {
+                line_size = child_size;
+            }
+        }
+        if line_start < children.len() {
+            lines.push(&children[line_start..]);
+        }
+        lines
+    }
+
+    /// The space a wrapping node breaks its children into lines within.
+    /// Widths are laid out before heights, so only horizontal layouts can use their final size.
+    fn wrap_size(&self, node: UIRef) -> f32 {
+        let node = self.get(node);
+        let axis = node.params.layout.axis;
+        let size = if axis == Axis::X {
+            node.rect.axis_range(axis).size()
+        } else {
+            node.basis_size.on_axis(axis)
+        };
+        (size - node.params.margin.total().on_axis(axis)).max(0.0)
+    }
+
+    /// The space a wrapping node breaks its children into lines within, if it is known before the node's basis size on the cross axis.
+    /// Widths are laid out before the basis heights are calculated, so this is only known for horizontal layouts.
+    fn basis_wrap_size(&self, node: UIRef) -> Option<f32> {
+        let layout = &self.get(node).params.layout;
+        (layout.wrap && layout.axis == Axis::X).then(|| self.wrap_size(node))
+    }
+
+    /// The lines a node's children are laid out in. A node that does not wrap has a single line.
+    fn layout_lines<'a>(&self, node: UIRef, children: &'a [UIRef]) -> Vec<&'a [UIRef]> {
+        let layout = &self.get(node).params.layout;
+        if layout.wrap {
+            self.wrap_lines(children, layout, self.wrap_size(node))
+        } else {
+            vec![children]
+        }
+    }
+
+    /// The size of a line of children on the cross axis
+    fn line_cross_size(&self, line: &[UIRef], axis: Axis) -> f32 {
+        line.iter().map(|child| self.get(*child).basis_size.on_axis(axis)).fold(0.0, f32::max)
+    }
+
     fn count_child_fractional_units(&self, node: UIRef, axis: Axis) -> f32 {
-        let on_main_axis = self.get(node).params.layout.axis == axis;
+        let on_main_axis = self.get(node).params.layout.flows_on_axis(axis);
 
         let mut frac_units = 0.0;
-        let mut child_ref = self.get(node).first_child;
-        while child_ref.is_some() {
+        for child_ref in self.flow_children(node) {
             let child = self.get(child_ref);
             if let SizeKind::Fr(frac) = child.params.size.on_axis(axis).size {
                 if on_main_axis {
@@ -165,7 +521,6 @@ impl UITree {
                     frac_units = frac_units.max(frac);
                 }
             }
-            child_ref = child.next; 
         }
 
         if matches!(self.get(node).params.size.on_axis(axis).size, SizeKind::Fit) {
@@ -211,30 +566,37 @@ impl UITree {
     }
 
     fn calc_content_basis_size(&mut self, node: UIRef, axis: Axis) -> f32 {
-        let layout_axis = self.get(node).params.layout.axis; 
+        let layout = self.get(node).params.layout.clone();
+        let children = self.flow_children(node);
 
-        if axis == layout_axis {
+        if layout.grid.is_some() {
+            self.grid_tracks_size(node, axis)
+        } else if layout.flows_on_axis(axis) {
             let mut content_size = 0.0;
-
-            let mut child_ref = self.get(node).first_child;
-            while child_ref.is_some() {
-                let child = self.get(child_ref);
-                content_size += child.basis_size.on_axis(axis);
-                child_ref = self.get(child_ref).next;
+            for child_ref in &children {
+                content_size += self.get(*child_ref).basis_size.on_axis(axis);
             }
 
-            content_size
+            content_size + Self::main_gaps_size(children.len(), &layout)
+        } else if let Some(wrap_size) = self.basis_wrap_size(node) {
+            let lines = self.wrap_lines(&children, &layout, wrap_size);
+            let gaps = lines.len().saturating_sub(1) as f32 * layout.cross_gap;
+            lines.iter().map(|line| self.line_cross_size(line, axis)).sum::<f32>() + gaps
         } else {
-            let mut content_size: f32 = 0.0;
-
-            let mut child_ref = self.get(node).first_child;
-            while child_ref.is_some() {
-                let child = self.get(child_ref);
-                content_size = content_size.max(child.basis_size.on_axis(axis));
-                child_ref = child.next;
-            }
+            self.line_cross_size(&children, axis)
+        }
+    }
 
-            content_size
+    /// The size of a node on an axis derived from its size on the other axis
+    fn calc_aspect_ratio_size(&self, node: UIRef, axis: Axis, ratio: f32) -> f32 {
+        let node = self.get(node);
+        match axis {
+            Axis::X => match node.params.size.y.size {
+                SizeKind::Px(height) => height * ratio,
+                _ => 0.0
+            },
+            // The final width is already known, since widths are laid out before heights
+            Axis::Y => (node.rect.width() - node.params.margin.total().x).max(0.0) / ratio.max(TINY)
         }
     }
 
@@ -244,17 +606,21 @@ impl UITree {
             self.calc_down_dependent_basis_size(memory, child, axis, text_resources);
             child = self.get(child).next;
         }
+        if self.get(node).params.layout.grid.is_some() {
+            self.calc_grid_content_tracks(node, axis);
+        }
 
         let frac_units = self.count_child_fractional_units(node, axis);
-        let size = self.get(node).params.size.on_axis(axis).size;
-        let basis_size = match size {
+        let size = *self.get(node).params.size.on_axis(axis);
+        let basis_size = match size.size {
             SizeKind::Px(size) => size,
             SizeKind::Text => self.calc_text_size(memory, node, axis, text_resources),
             SizeKind::Fr(_) | SizeKind::Fit => self.calc_content_basis_size(node, axis),
+            SizeKind::AspectRatio(ratio) => self.calc_aspect_ratio_size(node, axis, ratio)
         };
 
         let margin = self.get(node).params.margin.total().on_axis(axis);
-        *self.get_mut(node).basis_size.on_axis_mut(axis) = basis_size + margin;
+        *self.get_mut(node).basis_size.on_axis_mut(axis) = size.clamp(basis_size) + margin;
         *self.get_mut(node).frac_units.on_axis_mut(axis) = frac_units;
     }
 
@@ -270,33 +636,53 @@ impl UITree {
         let parent = self.get(node).parent;
         if parent.is_some() {
             if let SizeKind::Fr(frac) = self.get(node).params.size.on_axis(axis).size {
+                let (start, span) = *self.get(node).grid_placement.on_axis(axis);
+                let anchored = self.is_anchored(node);
                 let parent = self.get(parent);
-                let parent_basis = parent.basis_size.on_axis(axis);
-                let parent_frac_units = parent.frac_units.on_axis(axis);
-                *self.get_mut(node).basis_size.on_axis_mut(axis) = parent_basis * frac / parent_frac_units;
+                *self.get_mut(node).basis_size.on_axis_mut(axis) = if anchored {
+                    // Anchored nodes take up a fraction of the whole parent
+                    parent.basis_size.on_axis(axis) * frac.min(1.0)
+                } else if parent.params.layout.grid.is_some() {
+                    // In a grid, the node takes up a fraction of its cell
+                    Self::grid_cell_size(parent, axis, start, span) * frac.min(1.0)
+                } else {
+                    let parent_basis = parent.basis_size.on_axis(axis);
+                    let parent_frac_units = parent.frac_units.on_axis(axis);
+                    parent_basis * frac / parent_frac_units
+                };
+                let size = *self.get(node).params.size.on_axis(axis);
+                let basis_size = self.get_mut(node).basis_size.on_axis_mut(axis);
+                *basis_size = size.clamp(*basis_size);
             }
         }
 
         // Calculate the space taken up by the node's non-fractional children.
         // This is necessary to calculate how much space must be given to fractional children
         let mut non_frac_size = 0.0;
-        let mut child_ref = self.get(node).first_child;
-        while child_ref.is_some() {
-            let child = self.get(child_ref);
+        let flow_children = self.flow_children(node);
+        for child_ref in &flow_children {
+            let child = self.get(*child_ref);
             if !matches!(child.params.size.on_axis(axis).size, SizeKind::Fr(_)) {
                 non_frac_size += child.basis_size.on_axis(axis);
             }
-            child_ref = child.next;
+        }
+        let layout = self.get(node).params.layout.clone();
+        if layout.flows_on_axis(axis) {
+            non_frac_size += Self::main_gaps_size(flow_children.len(), &layout);
         }
 
         // If the node's size is not determined by the children(ie, if it's not SizeKind::Fit), calculate how many fractional units fit in the node
-        if axis == self.get(node).params.layout.axis && !matches!(self.get(node).params.size.on_axis(axis).size, SizeKind::Fit) {
+        if layout.flows_on_axis(axis) && !matches!(self.get(node).params.size.on_axis(axis).size, SizeKind::Fit) {
             let basis_size = self.get(node).basis_size.on_axis(axis);
             let frac_units = self.get(node).frac_units.on_axis(axis);
             let space_left = (basis_size - non_frac_size - margin).max(0.0);
             let frac_size = space_left / frac_units.max(TINY);
             *self.get_mut(node).frac_units.on_axis_mut(axis) = basis_size / frac_size.max(TINY); 
         }
+        if layout.grid.is_some() {
+            let basis_size = self.get(node).basis_size.on_axis(axis);
+            self.distribute_grid_fr_tracks(node, axis, basis_size);
+        }
 
         // Calculate the final basis sizes for all the children
         let mut child = self.get(node).first_child;
@@ -311,111 +697,401 @@ impl UITree {
 
     fn calc_layout_main_axis(&mut self, node: UIRef, node_id: Id, total_space: Range, axis: Axis, memory: &mut Memory) {
 
-        let layout = self.get(node).params.layout;
+        let layout = self.get(node).params.layout.clone();
         let space = self.get(node).params.margin.apply_on_axis(total_space, axis);
 
-        // The total basis size of all the children 
-        let mut total_size = 0.0;
-        // How many parts will the size violation be divided in?
-        let mut violation_denominator = 0.0;
-        // How many parts will the size underfill be divided in?
-        let mut underfill_denominator = 0.0;
+        let children = self.flow_children(node);
+        let mut children_base_size: f32 = 0.0;
+        for line in self.layout_lines(node, &children) {
+            children_base_size = children_base_size.max(self.calc_layout_main_axis_line(line, space, axis, &layout, memory));
+        }
 
-        let mut child_ref = self.get(node).first_child;
-        while child_ref.is_some() {
-            let child = self.get(child_ref);
-            let size = child.basis_size.on_axis(axis);
-            total_size += size;
+        *memory.get::<LayoutInfo>(node_id).children_base_size.on_axis_mut(axis) = children_base_size;
+    }
 
-            // If the child is allowed to shrink, it will share the violation
-            if child.params.size.on_axis(axis).shrink {
-                violation_denominator += size;
-            }
+    /// Lay out a line of children along the main axis, returning the total basis size of the line
+    fn calc_layout_main_axis_line(&mut self, line: &[UIRef], space: Range, axis: Axis, layout: &Layout, memory: &mut Memory) -> f32 {
 
-            underfill_denominator += child.params.size.on_axis(axis).grow;
-            child_ref = child.next;
+        // The total basis size of all the children 
+        let mut total_size = Self::main_gaps_size(line.len(), layout);
+        for child_ref in line {
+            total_size += self.get(*child_ref).basis_size.on_axis(axis);
         }
 
-        *memory.get::<LayoutInfo>(node_id).children_base_size.on_axis_mut(axis) = total_size;
+        // Children that would shrink or grow past their min or max size are frozen at that size,
+        // and the rest of the violation or underfill is shared between the other children
+        let mut frozen_sizes: Vec<Option<f32>> = vec![None; line.len()];
+        let mut sizes = vec![0.0; line.len()];
+        loop {
+            // The total size of the children, with the frozen children at their final size
+            let mut total_size = Self::main_gaps_size(line.len(), layout);
+            // How many parts will the size violation be divided in?
+            let mut violation_denominator = 0.0;
+            // How many parts will the size underfill be divided in?
+            let mut underfill_denominator = 0.0;
+
+            for (child_ref, frozen_size) in line.iter().zip(&frozen_sizes) {
+                if let Some(frozen_size) = frozen_size {
+                    total_size += frozen_size;
+                    continue;
+                }
 
-        let violation = if *layout.allow_overflow.on_axis(axis) { 0.0 } else { (total_size - space.size()).max(0.0) }; 
-        let violation_denominator_inv = if violation_denominator < 0.00001 { 1.0 } else { 1.0 / violation_denominator };
-        
-        let underfill = (space.size() - total_size).max(0.0); 
-        let underfill_denominator_inv = if underfill_denominator < 0.00001 { 1.0 } else { 1.0 / underfill_denominator }; 
-
-        let mut offset = if underfill_denominator < 0.00001 {
-            match layout.justify {
-                Justify::Min => 0.0,
-                Justify::Center => underfill / 2.0,
-                Justify::Max => underfill,
+                let child = self.get(*child_ref);
+                let size = child.basis_size.on_axis(axis);
+                total_size += size;
+
+                // If the child is allowed to shrink, it will share the violation
+                if child.params.size.on_axis(axis).shrink {
+                    violation_denominator += size;
+                }
+
+                underfill_denominator += child.params.size.on_axis(axis).grow;
             }
-        } else {
-            0.0
+
+            let violation = if *layout.allow_overflow.on_axis(axis) { 0.0 } else { (total_size - space.size()).max(0.0) }; 
+            let violation_denominator_inv = if violation_denominator < 0.00001 { 1.0 } else { 1.0 / violation_denominator };
+            
+            let underfill = (space.size() - total_size).max(0.0); 
+            let underfill_denominator_inv = if underfill_denominator < 0.00001 { 1.0 } else { 1.0 / underfill_denominator }; 
+
+            let mut froze_child = false;
+            for (idx, child_ref) in line.iter().enumerate() {
+                if let Some(frozen_size) = frozen_sizes[idx] {
+                    sizes[idx] = frozen_size;
+                    continue;
+                }
+                let child = self.get(*child_ref);
+                let shrink = if child.params.size.on_axis(axis).shrink {
+                    violation * child.basis_size.on_axis(axis) * violation_denominator_inv
+                } else {
+                    0.0
+                };
+                let grow = child.params.size.on_axis(axis).grow * underfill * underfill_denominator_inv;
+                let size = (child.basis_size.on_axis(axis) - shrink + grow).max(0.0); 
+                let clamped_size = self.clamp_layout_size(*child_ref, axis, size);
+                if clamped_size != size {
+                    frozen_sizes[idx] = Some(clamped_size);
+                    froze_child = true;
+                }
+                sizes[idx] = clamped_size;
+            }
+
+            if !froze_child {
+                break;
+            }
+        }
+
+        let underfill = (space.size() - Self::main_gaps_size(line.len(), layout) - sizes.iter().sum::<f32>()).max(0.0);
+        let n_children = line.len() as f32;
+        // Where the first child starts, and the leftover space added to the gap between children
+        let (mut offset, extra_gap) = match layout.justify {
+            Justify::Min => (0.0, 0.0),
+            Justify::Center => (underfill / 2.0, 0.0),
+            Justify::Max => (underfill, 0.0),
+            Justify::SpaceBetween if line.len() > 1 => (0.0, underfill / (n_children - 1.0)),
+            Justify::SpaceBetween => (0.0, 0.0),
+            Justify::SpaceAround => (underfill / n_children.max(1.0) / 2.0, underfill / n_children.max(1.0)),
+            Justify::SpaceEvenly => (underfill / (n_children + 1.0), underfill / (n_children + 1.0)),
         };
-        let mut child_ref = self.get(node).first_child;
-        while child_ref.is_some() {
-            let child = self.get_mut(child_ref);
-            let shrink = if child.params.size.on_axis(axis).shrink {
-                violation * child.basis_size.on_axis(axis) * violation_denominator_inv
-            } else {
-                0.0
-            };
-            let grow = child.params.size.on_axis(axis).grow * underfill * underfill_denominator_inv;
-            let size = (child.basis_size.on_axis(axis) - shrink + grow).max(0.0); 
+        for (child_ref, size) in line.iter().zip(sizes) {
             let child_space = Range::min_size(space.min + offset, size);
-            child.rect.set_axis_range(axis, child_space);
-            self.calc_layout(child_ref, self.get(child_ref).id, child_space, axis, memory);
-            child_ref = self.get(child_ref).next;
-            offset += size;
+            self.get_mut(*child_ref).rect.set_axis_range(axis, child_space);
+            self.calc_layout(*child_ref, self.get(*child_ref).id, child_space, axis, memory);
+            offset += size + layout.main_gap + extra_gap;
         }
 
+        total_size
     }
     
     fn calc_layout_cross_axis(&mut self, node: UIRef, node_id: Id, total_space: Range, axis: Axis, memory: &mut Memory) {
 
-        let layout = self.get(node).params.layout;
+        let layout = self.get(node).params.layout.clone();
         let space = self.get(node).params.margin.apply_on_axis(total_space, axis);
 
-        let mut child_base_size: f32 = 0.0;
-        
-        let mut child_ref = self.get(node).first_child;
-        while child_ref.is_some() {
-            let child = self.get_mut(child_ref);
-            let size = child.basis_size.on_axis(axis);
-            child_base_size = child_base_size.max(size);
-            let size = if size < space.size() {
-                if child.params.size.on_axis(axis).grow > 0.0 {
-                    space.size()
-                } else {
-                    size
-                }
+        // Lines of wrapped children are stacked along the cross axis
+        let children = self.flow_children(node);
+        let mut offset = 0.0;
+        for (idx, line) in self.layout_lines(node, &children).into_iter().enumerate() {
+            if idx > 0 {
+                offset += layout.cross_gap;
+            }
+            let line_size = self.line_cross_size(line, axis);
+            let line_space = if layout.wrap { Range::min_size(space.min + offset, line_size) } else { space };
+            self.calc_layout_cross_axis_line(line, line_space, axis, &layout, memory);
+            offset += line_size;
+        }
+
+        *memory.get::<LayoutInfo>(node_id).children_base_size.on_axis_mut(axis) = offset;
+    }
+
+    /// Lay out a line of children along the cross axis, aligning them within `space`
+    fn calc_layout_cross_axis_line(&mut self, line: &[UIRef], space: Range, axis: Axis, layout: &Layout, memory: &mut Memory) {
+        for child_ref in line {
+            let child_space = self.align_in_space(*child_ref, space, axis, layout.align, layout);
+            self.calc_layout(*child_ref, self.get(*child_ref).id, child_space, axis, memory);
+        }
+    }
+
+    /// Limit the size a node is laid out with, including its margins, to its min and max size
+    fn clamp_layout_size(&self, node: UIRef, axis: Axis, size: f32) -> f32 {
+        let node = self.get(node);
+        let margin = node.params.margin.total().on_axis(axis);
+        node.params.size.on_axis(axis).clamp(size - margin) + margin
+    }
+
+    /// Fit a child into `space`, growing or shrinking it if it is allowed to, and position it using `align`
+    fn align_in_space(&mut self, child_ref: UIRef, space: Range, axis: Axis, align: Align, layout: &Layout) -> Range {
+        let clamp = |tree: &Self, size: f32| tree.clamp_layout_size(child_ref, axis, size);
+        let space_size = clamp(self, space.size());
+        let child = self.get_mut(child_ref);
+        let size = child.basis_size.on_axis(axis);
+        let size = if size < space.size() {
+            if child.params.size.on_axis(axis).grow > 0.0 {
+                space_size
             } else {
-                if child.params.size.on_axis(axis).shrink && !layout.allow_overflow.on_axis(axis) {
-                    space.size()
-                } else {
-                    size
+                size
+            }
+        } else {
+            if child.params.size.on_axis(axis).shrink && !layout.allow_overflow.on_axis(axis) {
+                space_size
+            } else {
+                size
+            }
+        };
+        let child_space = match align {
+            Align::Min => Range::min_size(space.min, size),
+            Align::Center => Range::center_size(space.center(), size),
+            Align::Max => Range::max_size(space.max, size),
+        };
+        child.rect.set_axis_range(axis, child_space);
+        child_space
+    }
+
+    /// Decide which cells the children of grid layouts cover, filling the grid row by row
+    fn place_grid_children(&mut self, node: UIRef) {
+        for child in self.children(node) {
+            self.place_grid_children(child);
+        }
+
+        let Some(grid) = self.get(node).params.layout.grid.clone() else { return; };
+        let children = self.flow_children(node);
+        let n_columns = grid.columns.len().max(1);
+
+        // Which cells are taken, row by row
+        let mut occupied: Vec<bool> = Vec::new();
+        let is_free = |occupied: &Vec<bool>, column: usize, row: usize, span: PerAxis<usize>| {
+            (row..(row + span.y)).all(|row| (column..(column + span.x)).all(|column| !occupied.get(row * n_columns + column).copied().unwrap_or(false)))
+        };
+
+        let mut cursor = (0, 0);
+        let mut n_rows = grid.rows.len();
+        for child in children {
+            let cell = self.get(child).params.grid_cell;
+            let span = PerAxis::new(cell.span.x.clamp(1, n_columns), cell.span.y.max(1));
+            let (column, row) = match cell.position {
+                Some((column, row)) => (column.min(n_columns - span.x), row),
+                None => loop {
+                    if cursor.0 + span.x > n_columns {
+                        cursor = (0, cursor.1 + 1);
+                    }
+                    if is_free(&occupied, cursor.0, cursor.1, span) {
+                        break cursor;
+                    }
+                    cursor.0 += 1;
                 }
             };
-            let child_space = match layout.align {
-                Align::Min => Range::min_size(space.min, size),
-                Align::Center => Range::center_size(space.center(), size),
-                Align::Max => Range::max_size(space.max, size),
-            };
-            child.rect.set_axis_range(axis, child_space);
+            if cell.position.is_none() {
+                cursor.0 = column + span.x;
+            }
+
+            occupied.resize(occupied.len().max((row + span.y) * n_columns), false);
+            for row in row..(row + span.y) {
+                for column in column..(column + span.x) {
+                    occupied[row * n_columns + column] = true;
+                }
+            }
+            n_rows = n_rows.max(row + span.y);
+            self.get_mut(child).grid_placement = PerAxis::new((column, span.x), (row, span.y));
+        }
+
+        self.get_mut(node).grid_tracks = PerAxis::new(vec![0.0; n_columns], vec![0.0; n_rows]);
+    }
+
+    /// Size the columns or rows of a grid to fit the children in them
+    fn calc_grid_content_tracks(&mut self, node: UIRef, axis: Axis) {
+        let layout = self.get(node).params.layout.clone();
+        let Some(grid) = &layout.grid else { return; };
+        let gap = layout.grid_gap(axis);
+        let is_fixed = |idx: usize| matches!(grid.track(axis, idx).size, SizeKind::Px(_));
+
+        let mut tracks: Vec<f32> = (0..self.get(node).grid_tracks.on_axis(axis).len()).map(|idx| match grid.track(axis, idx).size {
+            SizeKind::Px(size) => size,
+            _ => 0.0
+        }).collect();
+
+        // Fit the children that cover a single track first...
+        let children = self.flow_children(node);
+        for child in &children {
+            let (start, span) = *self.get(*child).grid_placement.on_axis(axis);
+            if span == 1 && !is_fixed(start) {
+                tracks[start] = tracks[start].max(self.get(*child).basis_size.on_axis(axis));
+            }
+        }
+
+        // ...then grow the last track children spanning several tracks cover until they fit
+        for child in &children {
+            let (start, span) = *self.get(*child).grid_placement.on_axis(axis);
+            if span == 1 {
+                continue;
+            }
+            let covered = tracks[start..(start + span)].iter().sum::<f32>() + (span - 1) as f32 * gap;
+            let missing = self.get(*child).basis_size.on_axis(axis) - covered;
+            if missing > 0.0 {
+                if let Some(idx) = (start..(start + span)).rev().find(|idx| !is_fixed(*idx)) {
+                    tracks[idx] += missing;
+                }
+            }
+        }
+
+        for (idx, track) in tracks.iter_mut().enumerate() {
+            *track = grid.track(axis, idx).clamp(*track);
+        }
+
+        *self.get_mut(node).grid_content_tracks.on_axis_mut(axis) = tracks.clone();
+        *self.get_mut(node).grid_tracks.on_axis_mut(axis) = tracks;
+    }
+
+    /// Share the space left over by the other columns or rows of a grid between its fractional ones.
+    /// Fractional tracks that would be smaller than their content are frozen at their content size,
+    /// and the space left is shared between the others.
+    fn distribute_grid_fr_tracks(&mut self, node: UIRef, axis: Axis, size: f32) {
+        let layout = self.get(node).params.layout.clone();
+        let Some(grid) = &layout.grid else { return; };
+        let content_tracks = self.get(node).grid_content_tracks.on_axis(axis).clone();
+
+        let mut frozen = vec![false; content_tracks.len()];
+        let mut tracks = content_tracks.clone();
+        loop {
+            let mut fixed_size = tracks.len().saturating_sub(1) as f32 * layout.grid_gap(axis);
+            let mut frac_units = 0.0;
+            for (idx, track) in tracks.iter().enumerate() {
+                match grid.track(axis, idx).size {
+                    SizeKind::Fr(frac) if !frozen[idx] => frac_units += frac,
+                    _ => fixed_size += track
+                }
+            }
+            if frac_units < TINY {
+                break;
+            }
+
+            let frac_size = (size - fixed_size).max(0.0) / frac_units;
+            let mut froze_track = false;
+            for (idx, track) in tracks.iter_mut().enumerate() {
+                let track_size = grid.track(axis, idx);
+                let SizeKind::Fr(frac) = track_size.size else { continue; };
+                if frozen[idx] {
+                    continue;
+                }
+                *track = track_size.clamp(frac * frac_size);
+                if *track < content_tracks[idx] {
+                    *track = content_tracks[idx];
+                    frozen[idx] = true;
+                    froze_track = true;
+                }
+            }
+
+            if !froze_track {
+                break;
+            }
+        }
+
+        *self.get_mut(node).grid_tracks.on_axis_mut(axis) = tracks;
+    }
+
+    /// The total size of the columns or rows of a grid, including the gaps between them
+    fn grid_tracks_size(&self, node: UIRef, axis: Axis) -> f32 {
+        let node = self.get(node);
+        let tracks = node.grid_tracks.on_axis(axis);
+        tracks.iter().sum::<f32>() + tracks.len().saturating_sub(1) as f32 * node.params.layout.grid_gap(axis)
+    }
+
+    /// The size of a cell spanning `span` columns or rows of a grid, starting at `start`
+    fn grid_cell_size(grid_node: &UINode, axis: Axis, start: usize, span: usize) -> f32 {
+        let tracks = grid_node.grid_tracks.on_axis(axis);
+        let gap = grid_node.params.layout.grid_gap(axis);
+        tracks[start..(start + span)].iter().sum::<f32>() + (span - 1) as f32 * gap
+    }
+
+    fn calc_layout_grid(&mut self, node: UIRef, node_id: Id, total_space: Range, axis: Axis, memory: &mut Memory) {
+
+        let layout = self.get(node).params.layout.clone();
+        let space = self.get(node).params.margin.apply_on_axis(total_space, axis);
+        let children_base_size = self.grid_tracks_size(node, axis);
+        self.distribute_grid_fr_tracks(node, axis, space.size());
+
+        // Where each column or row starts
+        let mut track_starts = Vec::new();
+        let mut offset = space.min;
+        for track in self.get(node).grid_tracks.on_axis(axis) {
+            track_starts.push(offset);
+            offset += track + layout.grid_gap(axis);
+        }
+
+        let default_align = layout.cell_align(axis);
+
+        for child_ref in self.flow_children(node) {
+            let (start, span) = *self.get(child_ref).grid_placement.on_axis(axis);
+            let cell = Range::min_size(track_starts[start], Self::grid_cell_size(self.get(node), axis, start, span));
+            let align = self.get(child_ref).params.grid_cell.align.on_axis(axis).unwrap_or(default_align);
+            let child_space = self.align_in_space(child_ref, cell, axis, align, &layout);
             self.calc_layout(child_ref, self.get(child_ref).id, child_space, axis, memory);
-            child_ref = self.get(child_ref).next;
         }
 
-        *memory.get::<LayoutInfo>(node_id).children_base_size.on_axis_mut(axis) = child_base_size;
+        *memory.get::<LayoutInfo>(node_id).children_base_size.on_axis_mut(axis) = children_base_size;
+    }
+
+    fn calc_layout_stack(&mut self, node: UIRef, node_id: Id, total_space: Range, axis: Axis, memory: &mut Memory) {
+
+        let layout = self.get(node).params.layout.clone();
+        let space = self.get(node).params.margin.apply_on_axis(total_space, axis);
+        let align = layout.cell_align(axis);
+
+        let children = self.flow_children(node);
+        for child_ref in &children {
+            let child_space = self.align_in_space(*child_ref, space, axis, align, &layout);
+            self.calc_layout(*child_ref, self.get(*child_ref).id, child_space, axis, memory);
+        }
+
+        *memory.get::<LayoutInfo>(node_id).children_base_size.on_axis_mut(axis) = self.line_cross_size(&children, axis);
+    }
+
+    /// Place the anchored children of a node against the node's rect, inside its margin
+    fn calc_layout_anchored(&mut self, node: UIRef, total_space: Range, axis: Axis, memory: &mut Memory) {
+
+        let layout = self.get(node).params.layout.clone();
+        let space = self.get(node).params.margin.apply_on_axis(total_space, axis);
+
+        for child_ref in self.anchored_children(node) {
+            let Some(anchor) = self.get(child_ref).params.anchor else { continue; };
+            let child_space = self.align_in_space(child_ref, space, axis, *anchor.align.on_axis(axis), &layout);
+            let offset = anchor.offset.on_axis(axis);
+            let child_space = Range::new(child_space.min + offset, child_space.max + offset);
+            self.get_mut(child_ref).rect.set_axis_range(axis, child_space);
+            self.calc_layout(child_ref, self.get(child_ref).id, child_space, axis, memory);
+        }
     }
 
     fn calc_layout(&mut self, node: UIRef, node_id: Id, space: Range, axis: Axis, memory: &mut Memory) {
-        if axis == self.get(node).params.layout.axis {
+        if self.get(node).params.layout.grid.is_some() {
+            self.calc_layout_grid(node, node_id, space, axis, memory);
+        } else if self.get(node).params.layout.stack {
+            self.calc_layout_stack(node, node_id, space, axis, memory);
+        } else if axis == self.get(node).params.layout.axis {
             self.calc_layout_main_axis(node, node_id, space, axis, memory);
         } else {
             self.calc_layout_cross_axis(node, node_id, space, axis, memory);
         }
+        self.calc_layout_anchored(node, space, axis, memory);
     }
 
     fn calc_transformations(&mut self, node: UIRef, memory: &mut Memory, transform: TSTransform) {
@@ -439,21 +1115,22 @@ impl UITree {
 
         for layer in self.layers.clone() {
 
-            // Step 1: calculate down-dependent basis sizes
+            // Step 0: place the children of grids into cells
+            self.place_grid_children(layer);
+
+            // Widths are laid out before heights are calculated so that
+            // the heights of wrapping and aspect ratio nodes can depend on their final widths
+            self.get_mut(layer).rect = space;
             for axis in AXES {
+                // Step 1: calculate down-dependent basis sizes
                 self.calc_down_dependent_basis_size(memory, layer, axis, text_resources);
-            }
 
-            // Step 2: calculate up-dependent basis sizes
-            for axis in AXES {
+                // Step 2: calculate up-dependent basis sizes
                 self.calc_up_dependent_basis_size(layer, axis);
-            }
 
-            // Step 3: calculate layout
-            for axis in AXES {
+                // Step 3: calculate layout
                 self.calc_layout(layer, self.get(layer).id, space.axis_range(axis), axis, memory);
             }
-            self.get_mut(layer).rect = space;
 
             // Step 4: apply transformations
             self.calc_transformations(layer, memory, TSTransform::IDENTITY);
@@ -474,6 +1151,7 @@ impl UITree {
         layout_mem.sense_mouse = node.params.mouse;
         layout_mem.sense_scroll = node.params.scroll;
         layout_mem.has_interaction_priority = node.params.has_interaction_priority;
+        layout_mem.overlaps_siblings = node.params.anchor.is_some() || (node.parent.is_some() && self.get(node.parent).params.layout.stack);
 
         let mut child = node.first_child;
         while child.is_some() {
@@ -483,7 +1161,8 @@ impl UITree {
     }
 
     pub(crate) fn remember_layout(&self, memory: &mut Memory) {
-        memory.layer_ids = self.layers.iter().map(|layer| self.get(*layer).id).collect();
+        let layer_ids = self.layers.iter().map(|layer| self.get(*layer).id).collect();
+        memory.layer_ids.insert(self.root_id, layer_ids);
         for layer in &self.layers {
             self.remember_node_layout(*layer, memory);
         }
@@ -505,7 +1184,9 @@ pub(crate) struct LayoutMemory {
 
     pub(crate) sense_mouse: bool,
     pub(crate) sense_scroll: bool,
-    pub(crate) has_interaction_priority: bool
+    pub(crate) has_interaction_priority: bool,
+    /// Is the node drawn over its earlier siblings, because it is anchored or in a stack?
+    pub(crate) overlaps_siblings: bool
 }
 
 impl Default for LayoutMemory {
@@ -520,7 +1201,8 @@ impl Default for LayoutMemory {
             next: None,
             sense_mouse: false,
             sense_scroll: false,
-            has_interaction_priority: false
+            has_interaction_priority: false,
+            overlaps_siblings: false
         }
     }
 
@@ -566,3 +1248,6 @@ impl Default for TextSizeCache {
     }
 
 }
+
+#[cfg(test)]
+mod tests;
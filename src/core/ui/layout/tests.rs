@@ -0,0 +1,216 @@
+
+use crate::{container, stack, vec2, Align, Anchor, GridCell, Headless, Id, Justify, Layout, Rect, Size, UINodeParams, UI};
+
+fn boxed(ui: &mut UI, width: f32, height: f32) -> Id {
+    ui.node(UINodeParams::new(Size::px(width), Size::px(height))).id
+}
+
+fn rect(headless: &Headless, id: Id) -> Rect {
+    headless.node_rect(id).expect("node was not laid out")
+}
+
+fn rect_at(x: f32, y: f32, width: f32, height: f32) -> Rect {
+    Rect::min_size(vec2(x, y), vec2(width, height))
+}
+
+#[test]
+fn fit_wrap_under_narrower_parent() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let (wrap, items, below) = headless.tick(|ui| {
+        container(ui, Size::px(100.0), Size::fit(), Layout::vertical(), |ui| {
+            let (wrap, items) = container(ui, Size::fit(), Size::fit(), Layout::horizontal().with_wrap(), |ui| {
+                [(); 3].map(|_| boxed(ui, 40.0, 10.0))
+            });
+            (wrap.id, items, boxed(ui, 100.0, 5.0))
+        }).1
+    });
+
+    // The wrap node is shrunk to its parent's width, so its height fits two lines
+    assert_eq!(rect(&headless, wrap), rect_at(0.0, 0.0, 100.0, 20.0));
+    assert_eq!(rect(&headless, items[2]), rect_at(0.0, 10.0, 40.0, 10.0));
+    assert_eq!(rect(&headless, below).tl(), vec2(0.0, 20.0));
+}
+
+#[test]
+fn wrap_lines_with_gaps() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let (wrap, items) = headless.tick(|ui| {
+        let (wrap, items) = container(ui, Size::px(100.0), Size::fit(), Layout::horizontal().with_wrap().with_gap(10.0, 5.0), |ui| {
+            [(40.0, 10.0), (40.0, 20.0), (40.0, 10.0), (30.0, 10.0)].map(|(width, height)| boxed(ui, width, height))
+        });
+        (wrap.id, items)
+    });
+
+    // Each line is as tall as its tallest child, and the lines are separated by the cross gap
+    assert_eq!(rect(&headless, wrap), rect_at(0.0, 0.0, 100.0, 35.0));
+    assert_eq!(rect(&headless, items[0]), rect_at(0.0, 0.0, 40.0, 10.0));
+    assert_eq!(rect(&headless, items[1]), rect_at(50.0, 0.0, 40.0, 20.0));
+    assert_eq!(rect(&headless, items[2]), rect_at(0.0, 25.0, 40.0, 10.0));
+    assert_eq!(rect(&headless, items[3]), rect_at(50.0, 25.0, 30.0, 10.0));
+}
+
+fn cell(ui: &mut UI, width: f32, height: f32, cell: GridCell) -> Id {
+    ui.node(UINodeParams::new(Size::px(width), Size::px(height)).with_grid_cell(cell)).id
+}
+
+#[test]
+fn grid_placement_and_tracks() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let (grid, [a, b, c, d, e]) = headless.tick(|ui| {
+        let columns = vec![Size::px(50.0), Size::fit(), Size::fr(1.0)];
+        let (grid, cells) = container(ui, Size::px(200.0), Size::fit(), Layout::grid(columns).with_gap(10.0, 5.0), |ui| [
+            boxed(ui, 20.0, 10.0),
+            cell(ui, 30.0, 10.0, GridCell::at(1, 0).with_span(2, 1)),
+            // The rest of the first row is taken, so the next children start a new row
+            boxed(ui, 40.0, 20.0),
+            boxed(ui, 25.0, 10.0),
+            cell(ui, 5.0, 10.0, GridCell::AUTO.with_horizontal_align(Align::Max).with_vertical_align(Align::Center))
+        ]);
+        (grid.id, cells)
+    });
+
+    // The columns are 50, 25 (fitting d) and 105 (the space left) wide, and the rows fit their children
+    assert_eq!(rect(&headless, grid), rect_at(0.0, 0.0, 200.0, 35.0));
+    assert_eq!(rect(&headless, a), rect_at(0.0, 0.0, 20.0, 10.0));
+    assert_eq!(rect(&headless, b), rect_at(60.0, 0.0, 30.0, 10.0));
+    assert_eq!(rect(&headless, c), rect_at(0.0, 15.0, 40.0, 20.0));
+    assert_eq!(rect(&headless, d), rect_at(60.0, 15.0, 25.0, 10.0));
+    assert_eq!(rect(&headless, e), rect_at(195.0, 20.0, 5.0, 10.0));
+}
+
+#[test]
+fn grid_fr_tracks_and_spanning_cells() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let [a, b, c] = headless.tick(|ui| {
+        let layout = Layout::grid(vec![Size::fr(1.0), Size::fr(3.0)]).with_rows(vec![Size::px(20.0)]);
+        container(ui, Size::px(200.0), Size::fit(), layout, |ui| [
+            ui.node(UINodeParams::new(Size::fr(1.0), Size::fr(1.0))).id,
+            boxed(ui, 10.0, 10.0),
+            ui.node(UINodeParams::new(Size::fr(1.0), Size::px(10.0)).with_grid_cell(GridCell::at(0, 1).with_span(2, 1))).id
+        ]).1
+    });
+
+    // Fractional children fill their cell
+    assert_eq!(rect(&headless, a), rect_at(0.0, 0.0, 50.0, 20.0));
+    assert_eq!(rect(&headless, b), rect_at(50.0, 0.0, 10.0, 10.0));
+    assert_eq!(rect(&headless, c), rect_at(0.0, 20.0, 200.0, 10.0));
+}
+
+#[test]
+fn grid_fr_tracks_fit_their_content() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let [a, b, c] = headless.tick(|ui| {
+        let columns = vec![Size::fr(1.0), Size::fr(1.0), Size::fr(2.0)];
+        container(ui, Size::px(200.0), Size::fit(), Layout::grid(columns), |ui| [
+            boxed(ui, 80.0, 10.0),
+            boxed(ui, 10.0, 10.0),
+            boxed(ui, 10.0, 10.0)
+        ]).1
+    });
+
+    // The first column keeps the width of a, and the other columns share the rest
+    assert_eq!(rect(&headless, a), rect_at(0.0, 0.0, 80.0, 10.0));
+    assert_eq!(rect(&headless, b).left(), 80.0);
+    assert_eq!(rect(&headless, c).left(), 120.0);
+}
+
+#[test]
+fn min_and_max_sizes_freeze_children() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let [a, b, c, d] = headless.tick(|ui| {
+        let [a, b] = container(ui, Size::px(100.0), Size::fit(), Layout::horizontal(), |ui| [
+            ui.node(UINodeParams::new(Size::px(80.0).with_min(60.0), Size::px(10.0))).id,
+            boxed(ui, 80.0, 10.0)
+        ]).1;
+        let [c, d] = container(ui, Size::px(100.0), Size::fit(), Layout::horizontal(), |ui| [
+            ui.node(UINodeParams::new(Size::px(10.0).with_grow(1.0).with_max(30.0), Size::px(10.0))).id,
+            ui.node(UINodeParams::new(Size::px(10.0).with_grow(1.0), Size::px(10.0))).id
+        ]).1;
+        [a, b, c, d]
+    });
+
+    // a stops shrinking at its min size, so b takes the rest of the violation
+    assert_eq!(rect(&headless, a), rect_at(0.0, 0.0, 60.0, 10.0));
+    assert_eq!(rect(&headless, b), rect_at(60.0, 0.0, 40.0, 10.0));
+    // c stops growing at its max size, so d takes the rest of the underfill
+    assert_eq!(rect(&headless, c), rect_at(0.0, 10.0, 30.0, 10.0));
+    assert_eq!(rect(&headless, d), rect_at(30.0, 10.0, 70.0, 10.0));
+}
+
+#[test]
+fn aspect_ratio_on_both_axes() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let [a, b, c] = headless.tick(|ui| {
+        let a = ui.node(UINodeParams::new(Size::px(80.0), Size::aspect_ratio(2.0))).id;
+        let b = ui.node(UINodeParams::new(Size::aspect_ratio(2.0), Size::px(30.0))).id;
+        let c = container(ui, Size::px(100.0), Size::fit(), Layout::vertical(), |ui| {
+            ui.node(UINodeParams::new(Size::fr(1.0), Size::aspect_ratio(4.0))).id
+        }).1;
+        [a, b, c]
+    });
+
+    assert_eq!(rect(&headless, a), rect_at(0.0, 0.0, 80.0, 40.0));
+    assert_eq!(rect(&headless, b), rect_at(0.0, 40.0, 60.0, 30.0));
+    // The height follows the final width of a fractional child
+    assert_eq!(rect(&headless, c), rect_at(0.0, 70.0, 100.0, 25.0));
+}
+
+#[test]
+fn justify_with_space() {
+    for (justify, width, gap, expected) in [
+        (Justify::SpaceBetween, 90.0, 0.0, [0.0, 40.0, 80.0]),
+        (Justify::SpaceBetween, 100.0, 5.0, [0.0, 45.0, 90.0]),
+        (Justify::SpaceAround, 90.0, 0.0, [10.0, 40.0, 70.0]),
+        (Justify::SpaceEvenly, 110.0, 0.0, [20.0, 50.0, 80.0])
+    ] {
+        let mut headless = Headless::new(vec2(400.0, 300.0));
+        let items = headless.tick(|ui| {
+            container(ui, Size::px(width), Size::fit(), Layout::horizontal().with_justify(justify).with_main_gap(gap), |ui| {
+                [(); 3].map(|_| boxed(ui, 10.0, 10.0))
+            }).1
+        });
+        assert_eq!(items.map(|item| rect(&headless, item).left()), expected);
+    }
+}
+
+#[test]
+fn anchored_children() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let [a, badge, b, half] = headless.tick(|ui| {
+        container(ui, Size::px(100.0), Size::px(50.0), Layout::horizontal().with_main_gap(10.0), |ui| [
+            boxed(ui, 20.0, 10.0),
+            ui.node(UINodeParams::new(Size::px(10.0), Size::px(10.0)).with_anchor(Anchor::top_right().with_offset(vec2(-5.0, 5.0)))).id,
+            boxed(ui, 20.0, 10.0),
+            ui.node(UINodeParams::new(Size::fr(0.5), Size::px(10.0)).with_anchor(Anchor::center())).id
+        ]).1
+    });
+
+    // Anchored children take up no space, so there is a single gap between a and b
+    assert_eq!(rect(&headless, a), rect_at(0.0, 0.0, 20.0, 10.0));
+    assert_eq!(rect(&headless, b), rect_at(30.0, 0.0, 20.0, 10.0));
+    assert_eq!(rect(&headless, badge), rect_at(85.0, 5.0, 10.0, 10.0));
+    assert_eq!(rect(&headless, half), rect_at(25.0, 20.0, 50.0, 10.0));
+}
+
+#[test]
+fn stack_alignment() {
+    let mut headless = Headless::new(vec2(400.0, 300.0));
+    let ([a, b], fit, [c, d]) = headless.tick(|ui| {
+        let aligned = container(ui, Size::px(100.0), Size::px(50.0), Layout::stack().justify_center().align_max(), |ui| [
+            boxed(ui, 20.0, 10.0),
+            boxed(ui, 40.0, 30.0)
+        ]).1;
+        let (fit, fitted) = stack(ui, |ui| [
+            boxed(ui, 20.0, 10.0),
+            boxed(ui, 40.0, 30.0)
+        ]);
+        (aligned, fit.id, fitted)
+    });
+
+    assert_eq!(rect(&headless, a), rect_at(40.0, 40.0, 20.0, 10.0));
+    assert_eq!(rect(&headless, b), rect_at(30.0, 20.0, 40.0, 30.0));
+    // A stack that fits its children is as big as the biggest one
+    assert_eq!(rect(&headless, fit), rect_at(0.0, 50.0, 40.0, 30.0));
+    assert_eq!(rect(&headless, c), rect_at(0.0, 50.0, 20.0, 10.0));
+    assert_eq!(rect(&headless, d), rect_at(0.0, 50.0, 40.0, 30.0));
+}
@@ -1,15 +1,28 @@
 
 use std::{any::{Any, TypeId}, collections::HashMap, u64};
 
-use super::UITree;
+use super::{Shortcuts, UIQuery, UITree};
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
 pub struct Id(pub(crate) u64);
 
+impl Id {
+
+    /// The root id of the main window's tree
+    pub(crate) const ROOT: Self = Self(0);
+
+}
+
 pub struct Memory {
-    pub(crate) layer_ids: Vec<Id>,
+    /// The ids of the layers of each window in the last frame, keyed by the window's root id
+    pub(crate) layer_ids: HashMap<Id, Vec<Id>>,
+    /// A snapshot of the nodes of each window in the last frame, keyed by the window's root id
+    pub(crate) queries: HashMap<Id, UIQuery>,
+    /// Are snapshots of the nodes taken every frame? Off until a snapshot is asked for, since taking them copies every node
+    pub(crate) take_queries: bool,
     focused: Option<Id>,
     dnd_payload: Option<Box<dyn Any>>,
+    shortcuts: Shortcuts,
     memory: HashMap<(Id, TypeId), Box<dyn Any>>
 }
 
@@ -17,13 +30,23 @@ impl Memory {
 
     pub(crate) fn new() -> Self {
         Self {
-            layer_ids: Vec::new(),
+            layer_ids: HashMap::new(),
+            queries: HashMap::new(),
+            take_queries: false,
             focused: None,
             dnd_payload: None,
+            shortcuts: Shortcuts::default(),
             memory: HashMap::new(),
         }
     }
 
+    /// The snapshot of a window's nodes in the last frame, keyed by the window's root id.
+    /// Starts taking snapshots from the next frame on if they were not taken yet, returning an empty snapshot until then.
+    pub(crate) fn query(&mut self, root_id: Id) -> &UIQuery {
+        self.take_queries = true;
+        self.queries.get(&root_id).unwrap_or(UIQuery::empty())
+    }
+
     /// Focus on a node
     pub fn request_focus(&mut self, node: Id) {
         self.focused = Some(node);
@@ -69,6 +92,11 @@ impl Memory {
         self.dnd_payload = None;
     }
 
+    /// The registry of keyboard shortcuts
+    pub fn shortcuts(&mut self) -> &mut Shortcuts {
+        &mut self.shortcuts
+    }
+
     /// Get a reference to the data of type `T` for a certain node.
     /// Sets node's data to `T::default()` if it didn't previously exist.
     pub fn get<T: Default + Any>(&mut self, id: Id) -> &mut T {
@@ -114,11 +142,13 @@ impl Memory {
     }
 
     /// Remove memory for all ui nodes that are not in the ui tree.
-    pub(crate) fn garbage_collect(&mut self, tree: &UITree) {
+    /// `other_live_nodes` are the nodes of other windows, which should also be kept.
+    pub(crate) fn garbage_collect(&mut self, tree: &UITree, other_live_nodes: &[Id]) {
         let mut live_nodes = ahash::AHashSet::new();
         for node in &tree.nodes {
             live_nodes.insert(node.id);
         }
+        live_nodes.extend(other_live_nodes.iter().copied());
 
         self.memory.retain(|(id, _), _| live_nodes.contains(id));
 
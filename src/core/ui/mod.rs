@@ -18,25 +18,44 @@ mod paint;
 mod tree;
 pub use tree::*;
 
+mod query;
+pub use query::*;
+
 mod cursor;
 pub use cursor::*;
 
 mod clipboard;
 
+mod shortcut;
+pub use shortcut::*;
+
+mod window;
+pub(crate) use window::*;
+
 use crate::{Color, Rect, Vec2};
 
-use super::{text::FontId, Margin, Painter, PerAxis, RenderResources, Stroke, TSTransform};
+use super::{text::{FontId, TextResources}, Margin, Painter, PerAxis, Stroke, TSTransform};
 
-pub struct UI<'a, 'b> {
+pub struct UI<'a> {
     input: &'a Input,
     memory: &'a mut Memory,
     style: Style,
 
-    render_resources: &'a mut RenderResources<'b>,
+    text_resources: &'a mut TextResources,
     clipboard: Option<&'a mut arboard::Clipboard>,
+    device: Option<&'a wgpu::Device>,
+    queue: Option<&'a wgpu::Queue>,
 
     window_size: Vec2,
 
+    // windows
+    /// The state of the windows opened with `open_window`. `None` if windows cannot be opened
+    windows: Option<&'a mut ChildWindows>,
+    /// The nodes of the child windows built during this frame, kept alive during garbage collection
+    child_window_nodes: Vec<Id>,
+    /// Is this the UI of a child window?
+    is_child_window: bool,
+
     // tree-building
     tree: UITree,
     parent_stack: Vec<UIRef>,
@@ -48,16 +67,39 @@ pub struct UI<'a, 'b> {
     pub(crate) request_ime: Option<UIRef>
 }
 
-impl<'a, 'b> UI<'a, 'b> {
+/// What the UI produced over the course of a frame, after layout
+pub(crate) struct FrameOutput {
+    pub(crate) tree: UITree,
+    pub(crate) request_redraw: bool,
+    pub(crate) cursor: CursorIcon,
+    pub(crate) request_ime: Option<UIRef>
+}
 
-    pub(crate) fn new(input: &'a Input, memory: &'a mut Memory, render_resources: &'a mut RenderResources<'b>, clipboard: Option<&'a mut arboard::Clipboard>, window_size: Vec2, tree: UITree, layer: UIRef) -> Self {
+impl<'a> UI<'a> {
+
+    pub(crate) fn new(
+        input: &'a Input,
+        memory: &'a mut Memory,
+        text_resources: &'a mut TextResources,
+        clipboard: Option<&'a mut arboard::Clipboard>,
+        device: Option<&'a wgpu::Device>,
+        queue: Option<&'a wgpu::Queue>,
+        window_size: Vec2
+    ) -> Self {
+        let mut tree = UITree::new();
+        let layer = tree.add_layer(window_size); 
         Self {
             input,
             memory,
             style: Style::new(),
-            render_resources,
+            text_resources,
             clipboard,
+            device,
+            queue,
             window_size,
+            windows: None,
+            child_window_nodes: Vec::new(),
+            is_child_window: false,
             tree,
             parent_stack: vec![layer],
             curr_sibling: UIRef::Null,
@@ -67,8 +109,41 @@ impl<'a, 'b> UI<'a, 'b> {
         }
     }
 
-    pub(crate) fn tree(self) -> UITree {
-        self.tree
+    /// Allow the UI to open child windows
+    pub(crate) fn with_child_windows(mut self, windows: &'a mut ChildWindows) -> Self {
+        self.windows = Some(windows);
+        self
+    }
+
+    /// Make this the UI of a child window, with its own tree root
+    fn in_child_window(mut self, window_id: Id) -> Self {
+        self.tree = UITree::with_root(window_id);
+        let layer = self.tree.add_layer(self.window_size);
+        self.parent_stack = vec![layer];
+        self.is_child_window = true;
+        self
+    }
+
+    /// Finish building the UI tree, then lay it out.
+    pub(crate) fn finish(self) -> FrameOutput {
+        let mut tree = self.tree;
+
+        // Child windows are built during the main window's frame, so the main window collects the garbage for them
+        if !self.is_child_window {
+            self.memory.garbage_collect(&tree, &self.child_window_nodes);
+        }
+        tree.layout(Rect::min_size(Vec2::ZERO, self.window_size), self.memory, self.text_resources);
+        tree.remember_layout(self.memory);
+        if self.memory.take_queries {
+            self.memory.queries.insert(tree.root_id, tree.query());
+        }
+
+        FrameOutput {
+            tree,
+            request_redraw: self.request_redraw,
+            cursor: self.cursor,
+            request_ime: self.request_ime
+        }
     }
 
     pub fn curr_parent(&self) -> UIRef {
@@ -109,6 +184,9 @@ impl<'a, 'b> UI<'a, 'b> {
             through_hovered: interaction.through_hovered,
             l_mouse: interaction.l_mouse,
             r_mouse: interaction.r_mouse,
+            m_mouse: interaction.m_mouse,
+            back_mouse: interaction.back_mouse,
+            forward_mouse: interaction.forward_mouse,
             scroll: interaction.scroll
         }
     }
@@ -164,6 +242,12 @@ impl<'a, 'b> UI<'a, 'b> {
         &mut self.memory
     }
 
+    /// The nodes of this window in the last frame, e.g. for an inspector or for automating the UI.
+    /// Snapshots of the nodes are only taken once one is asked for, so this is empty the first time it is called.
+    pub fn prev_frame_query(&mut self) -> &UIQuery {
+        self.memory.query(self.tree.root_id)
+    }
+
     pub fn style<T: Default + Any>(&mut self) -> &T {
         self.style.get()
     }
@@ -198,6 +282,10 @@ impl<'a, 'b> UI<'a, 'b> {
         self.tree.get_mut(node).params.text = Some(text.into());
     }
 
+    pub fn set_role(&mut self, node: UIRef, role: Role) {
+        self.tree.get_mut(node).params.role = role;
+    }
+
     pub fn set_sense_mouse(&mut self, node: UIRef, mouse: bool) {
         self.tree.get_mut(node).params.mouse = mouse;
     }
@@ -218,28 +306,42 @@ impl<'a, 'b> UI<'a, 'b> {
         self.request_ime = Some(node);
     }
 
-    /// Get the WebGPU render device
+    /// Get the WebGPU render device.
+    /// Panics if the UI is not being rendered with WebGPU, e.g. when running headless. Use `try_wgpu_device` in code that can run headless.
     pub fn wgpu_device(&mut self) -> &wgpu::Device {
-        &self.render_resources.device
+        self.try_wgpu_device().expect("UI is not rendered with WebGPU")
     } 
 
-    /// Get the WebGPU render queue
+    /// Get the WebGPU render queue.
+    /// Panics if the UI is not being rendered with WebGPU, e.g. when running headless. Use `try_wgpu_queue` in code that can run headless.
     pub fn wgpu_queue(&mut self) -> &wgpu::Queue {
-        &self.render_resources.queue
+        self.try_wgpu_queue().expect("UI is not rendered with WebGPU")
+    }
+
+    /// Get the WebGPU render device.
+    /// Returns `None` if the UI is not being rendered with WebGPU, e.g. when running headless
+    pub fn try_wgpu_device(&mut self) -> Option<&wgpu::Device> {
+        self.device
+    }
+
+    /// Get the WebGPU render queue.
+    /// Returns `None` if the UI is not being rendered with WebGPU, e.g. when running headless
+    pub fn try_wgpu_queue(&mut self) -> Option<&wgpu::Queue> {
+        self.queue
     }
 
     /// Get the COSMIC Text font system
     pub fn font_system(&mut self, font_id: FontId) -> Option<&mut cosmic_text::FontSystem> {
-        let font = self.render_resources.text_resources.fonts.get_mut(&font_id)?;
+        let font = self.text_resources.fonts.get_mut(&font_id)?;
         Some(&mut font.font_system)
     }
 
     pub fn text_font(&self) -> FontId {
-        self.render_resources.text_resources.text_font
+        self.text_resources.text_font
     }
     
     pub fn icon_font(&self) -> FontId {
-        self.render_resources.text_resources.icon_font
+        self.text_resources.icon_font
     }
 
 }
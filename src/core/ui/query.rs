@@ -0,0 +1,160 @@
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::{Color, Rect, Stroke};
+
+use super::{Id, Role, UIRef, UITree};
+
+/// Information about a single node in a frame of the UI
+#[derive(Clone, Debug)]
+pub struct NodeInfo {
+    pub id: Id,
+    pub parent: Option<Id>,
+    pub children: Vec<Id>,
+    /// The index of the layer the node is in
+    pub layer: usize,
+
+    pub role: Role,
+    /// The rectangle covered by the node, in screen space
+    pub rect: Rect,
+    pub fill: Color,
+    pub stroke: Stroke,
+    pub rounding: f32,
+    pub text: Option<String>,
+
+    pub sense_mouse: bool,
+    pub sense_scroll: bool
+}
+
+/// A snapshot of every node in a frame of the UI, for finding nodes in tests and tooling.
+/// Nodes are listed depth-first, layer by layer, in the order they are drawn.
+#[derive(Clone, Debug)]
+pub struct UIQuery {
+    nodes: Vec<NodeInfo>,
+    /// The index of each node in `nodes`
+    indices: HashMap<Id, usize>
+}
+
+impl UIQuery {
+
+    pub(crate) fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new()
+        }
+    }
+
+    /// The snapshot of a window that has not been built yet
+    pub(crate) fn empty() -> &'static Self {
+        static EMPTY: OnceLock<UIQuery> = OnceLock::new();
+        EMPTY.get_or_init(Self::new)
+    }
+
+    pub fn nodes(&self) -> &[NodeInfo] {
+        &self.nodes
+    }
+
+    pub fn get(&self, id: Id) -> Option<&NodeInfo> {
+        self.nodes.get(*self.indices.get(&id)?)
+    }
+
+    /// Find the first node matching a predicate
+    pub fn find<P: Fn(&NodeInfo) -> bool>(&self, predicate: P) -> Option<&NodeInfo> {
+        self.nodes.iter().find(|node| predicate(node))
+    }
+
+    /// Find all nodes matching a predicate
+    pub fn find_all<P: Fn(&NodeInfo) -> bool>(&self, predicate: P) -> impl Iterator<Item = &NodeInfo> {
+        self.nodes.iter().filter(move |node| predicate(node))
+    }
+
+    /// Find the first node whose text is exactly `text`
+    pub fn find_by_text(&self, text: &str) -> Option<&NodeInfo> {
+        self.find(|node| node.text.as_deref() == Some(text))
+    }
+
+    /// Find the first node whose text contains `text`
+    pub fn find_by_text_containing(&self, text: &str) -> Option<&NodeInfo> {
+        self.find(|node| node.text.as_deref().is_some_and(|node_text| node_text.contains(text)))
+    }
+
+    /// Find all nodes with a given role
+    pub fn find_by_role(&self, role: Role) -> impl Iterator<Item = &NodeInfo> {
+        self.find_all(move |node| node.role == role)
+    }
+
+    /// Find the first node with a given role whose text, or whose descendant's text, is exactly `text`.
+    /// Useful for widgets like labeled checkboxes, where the text is stored in a different node.
+    pub fn find_by_role_and_text(&self, role: Role, text: &str) -> Option<&NodeInfo> {
+        self.find_by_role(role).find(|node| self.subtree_has_text(node, text))
+    }
+
+    fn subtree_has_text(&self, node: &NodeInfo, text: &str) -> bool {
+        node.text.as_deref() == Some(text) || node.children.iter()
+            .filter_map(|child| self.get(*child))
+            .any(|child| self.subtree_has_text(child, text))
+    }
+
+    pub fn parent(&self, id: Id) -> Option<&NodeInfo> {
+        self.get(self.get(id)?.parent?)
+    }
+
+    pub fn children(&self, id: Id) -> impl Iterator<Item = &NodeInfo> {
+        self.get(id)
+            .map(|node| node.children.as_slice())
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|child| self.get(*child))
+    }
+
+}
+
+impl UITree {
+
+    fn query_node(&self, node_ref: UIRef, layer: usize, parent: Option<Id>, nodes: &mut Vec<NodeInfo>) {
+        let node = self.get(node_ref);
+
+        let mut children = Vec::new();
+        let mut child = node.first_child;
+        while child.is_some() {
+            children.push(self.get(child).id);
+            child = self.get(child).next;
+        }
+
+        nodes.push(NodeInfo {
+            id: node.id,
+            parent,
+            children,
+            layer,
+            role: node.params.role,
+            rect: node.transform * node.rect,
+            fill: node.params.fill,
+            stroke: node.params.stroke,
+            rounding: node.params.rounding,
+            text: node.params.text.clone(),
+            sense_mouse: node.params.mouse,
+            sense_scroll: node.params.scroll
+        });
+
+        let mut child = node.first_child;
+        while child.is_some() {
+            self.query_node(child, layer, Some(node.id), nodes);
+            child = self.get(child).next;
+        }
+    }
+
+    /// Take a snapshot of the tree for querying.
+    /// Must be called before painting, since painting consumes the text of the nodes.
+    pub(crate) fn query(&self) -> UIQuery {
+        let mut nodes = Vec::new();
+        for (idx, layer) in self.layers.iter().enumerate() {
+            self.query_node(*layer, idx, None, &mut nodes);
+        }
+        let indices = nodes.iter().enumerate().map(|(idx, node)| (node.id, idx)).collect();
+        UIQuery {
+            nodes,
+            indices
+        }
+    }
+
+}
@@ -0,0 +1,325 @@
+
+use std::{collections::HashMap, fmt::Display};
+
+use super::{Id, Input, KeyCode, KeyEvent, LayoutMemory, LogicalKey, Memory, UI};
+
+/// The key at the heart of a key chord
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChordKey {
+    Logical(LogicalKey),
+    /// A key that types a character, stored in lowercase
+    Text(char),
+    /// A key at a physical location on the keyboard, which stays in the same place across keyboard layouts
+    Physical(KeyCode)
+}
+
+/// A key pressed while holding down a set of modifiers, e.g. Ctrl+Shift+Z
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyChord {
+    pub key: ChordKey,
+    /// Is Ctrl held down? Cmd on macOS.
+    pub command: bool,
+    pub shift: bool,
+    /// Is Alt held down? Option on macOS.
+    pub alt: bool
+}
+
+impl KeyChord {
+
+    pub const fn new(key: ChordKey) -> Self {
+        Self {
+            key,
+            command: false,
+            shift: false,
+            alt: false
+        }
+    }
+
+    pub const fn logical(key: LogicalKey) -> Self {
+        Self::new(ChordKey::Logical(key))
+    }
+
+    pub const fn text(char: char) -> Self {
+        Self::new(ChordKey::Text(char.to_ascii_lowercase()))
+    }
+
+    pub const fn physical(key: KeyCode) -> Self {
+        Self::new(ChordKey::Physical(key))
+    }
+
+    pub const fn with_command(self) -> Self {
+        Self {
+            command: true,
+            ..self
+        }
+    }
+
+    pub const fn with_shift(self) -> Self {
+        Self {
+            shift: true,
+            ..self
+        }
+    }
+
+    pub const fn with_alt(self) -> Self {
+        Self {
+            alt: true,
+            ..self
+        }
+    }
+
+    /// Does a key event press this chord?
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let modifiers = event.modifiers;
+        let modifiers_match = modifiers.command() == self.command && modifiers.shift() == self.shift && modifiers.alt() == self.alt;
+        let key_matches = match self.key {
+            ChordKey::Logical(logical_key) => event.key.logical_key == Some(logical_key),
+            ChordKey::Text(char) => event.key.text.as_ref().is_some_and(|text| text.to_lowercase() == char.to_string()),
+            ChordKey::Physical(physical_key) => event.physical_key == Some(physical_key)
+        };
+        event.pressed && modifiers_match && key_matches
+    }
+
+    /// Was the chord pressed this frame?
+    pub fn pressed(&self, input: &Input) -> bool {
+        input.key_events.iter().any(|event| self.matches(event))
+    }
+
+    /// The chord pressed this frame, if any. Useful for letting the user pick a new binding for a shortcut.
+    /// Keys without a logical meaning or text, like some media keys, are identified by their physical location.
+    pub fn from_input(input: &Input) -> Option<Self> {
+        const MODIFIER_CODES: &[KeyCode] = &[
+            KeyCode::AltLeft, KeyCode::AltRight, KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::ShiftLeft, KeyCode::ShiftRight,
+            KeyCode::SuperLeft, KeyCode::SuperRight, KeyCode::CapsLock, KeyCode::Fn
+        ];
+        input.key_events.iter().filter(|event| event.pressed).find_map(|event| {
+            let key = if let Some(logical_key) = event.key.logical_key {
+                (!logical_key.is_modifier()).then_some(ChordKey::Logical(logical_key))?
+            } else if let Some(char) = event.key.text.as_ref().and_then(|text| text.to_lowercase().chars().next()) {
+                ChordKey::Text(char)
+            } else {
+                event.physical_key.filter(|physical_key| !MODIFIER_CODES.contains(physical_key)).map(ChordKey::Physical)?
+            };
+            Some(Self {
+                key,
+                command: event.modifiers.command(),
+                shift: event.modifiers.shift(),
+                alt: event.modifiers.alt()
+            })
+        })
+    }
+
+}
+
+impl Display for KeyChord {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.command {
+            f.write_str(if cfg!(target_os = "macos") { "Cmd+" } else { "Ctrl+" })?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        if self.alt {
+            f.write_str(if cfg!(target_os = "macos") { "Option+" } else { "Alt+" })?;
+        }
+        match self.key {
+            ChordKey::Logical(logical_key) => write!(f, "{:?}", logical_key),
+            ChordKey::Text(char) => write!(f, "{}", char.to_uppercase()),
+            ChordKey::Physical(physical_key) => write!(f, "{:?}", physical_key)
+        }
+    }
+
+}
+
+/// A command that can be triggered with a keyboard shortcut.
+/// Usually declared as a constant and checked with `UI::shortcut_pressed`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Shortcut {
+    /// A unique, persistent name for the shortcut, used to store user bindings
+    pub id: &'static str,
+    /// The name shown to the user
+    pub name: &'static str,
+    /// The part of the application the shortcut is used in.
+    /// Shortcuts in different contexts can share a key chord without conflicting.
+    pub context: &'static str,
+    pub default_chord: Option<KeyChord>
+}
+
+impl Shortcut {
+
+    pub const fn new(id: &'static str, name: &'static str, default_chord: KeyChord) -> Self {
+        Self {
+            id,
+            name,
+            context: "",
+            default_chord: Some(default_chord)
+        }
+    }
+
+    /// A shortcut without a key chord until the user binds one
+    pub const fn unbound(id: &'static str, name: &'static str) -> Self {
+        Self {
+            id,
+            name,
+            context: "",
+            default_chord: None
+        }
+    }
+
+    pub const fn with_context(self, context: &'static str) -> Self {
+        Self {
+            context,
+            ..self
+        }
+    }
+
+}
+
+/// The key chords the user has chosen in place of the defaults, keyed by shortcut id.
+/// `None` means the user removed the shortcut's binding.
+/// Enable the `serde` feature to serialize it.
+#[derive(Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShortcutBindings {
+    pub bindings: HashMap<String, Option<KeyChord>>
+}
+
+/// The registry of keyboard shortcuts, along with the user's bindings
+#[derive(Default)]
+pub struct Shortcuts {
+    /// The shortcuts that have been registered or checked, in the order they were first seen
+    shortcuts: Vec<Shortcut>,
+    bindings: ShortcutBindings,
+    /// The chords claimed by scoped shortcuts, along with the node that was focused when they were claimed
+    claimed_chords: HashMap<KeyChord, Id>
+}
+
+impl Shortcuts {
+
+    /// Make a shortcut known to the registry, e.g. so that it can be listed in a settings menu before it is first used
+    pub fn register(&mut self, shortcut: &Shortcut) {
+        if !self.shortcuts.iter().any(|other| other.id == shortcut.id) {
+            self.shortcuts.push(*shortcut);
+        }
+    }
+
+    /// Every registered shortcut
+    pub fn shortcuts(&self) -> &[Shortcut] {
+        &self.shortcuts
+    }
+
+    /// The key chord currently bound to a shortcut
+    pub fn chord(&self, shortcut: &Shortcut) -> Option<KeyChord> {
+        match self.bindings.bindings.get(shortcut.id) {
+            Some(chord) => *chord,
+            None => shortcut.default_chord
+        }
+    }
+
+    /// Bind a shortcut to a different key chord, or remove its binding with `None`
+    pub fn rebind(&mut self, shortcut: &Shortcut, chord: Option<KeyChord>) {
+        self.register(shortcut);
+        self.bindings.bindings.insert(shortcut.id.to_owned(), chord);
+    }
+
+    /// Go back to the default key chord for a shortcut
+    pub fn reset(&mut self, shortcut: &Shortcut) {
+        self.bindings.bindings.remove(shortcut.id);
+    }
+
+    pub fn bindings(&self) -> &ShortcutBindings {
+        &self.bindings
+    }
+
+    /// Replace the user's bindings, e.g. with ones loaded from a settings file
+    pub fn set_bindings(&mut self, bindings: ShortcutBindings) {
+        self.bindings = bindings;
+    }
+
+    /// The registered shortcuts in the same context as `shortcut` that are bound to the same key chord
+    pub fn conflicts_with(&self, shortcut: &Shortcut) -> Vec<Shortcut> {
+        let Some(chord) = self.chord(shortcut) else { return Vec::new(); };
+        self.shortcuts.iter()
+            .filter(|other| other.id != shortcut.id && other.context == shortcut.context && self.chord(other) == Some(chord))
+            .copied()
+            .collect()
+    }
+
+    /// Groups of registered shortcuts in the same context that are bound to the same key chord
+    pub fn conflicts(&self) -> Vec<Vec<Shortcut>> {
+        let mut groups: Vec<((&'static str, KeyChord), Vec<Shortcut>)> = Vec::new();
+        for shortcut in &self.shortcuts {
+            let Some(chord) = self.chord(shortcut) else { continue; };
+            let key = (shortcut.context, chord);
+            match groups.iter_mut().find(|(other_key, _)| *other_key == key) {
+                Some((_, group)) => group.push(*shortcut),
+                None => groups.push((key, vec![*shortcut]))
+            }
+        }
+        groups.into_iter().map(|(_, group)| group).filter(|group| group.len() > 1).collect()
+    }
+
+}
+
+/// Is `node` the root of a subtree or one of its descendants in the last frame?
+fn subtree_contains(memory: &mut Memory, root: Id, node: Id) -> bool {
+    if root == node {
+        return true;
+    }
+    let mut child = memory.get_opt::<LayoutMemory>(root).and_then(|layout| layout.first_child);
+    while let Some(child_id) = child {
+        if subtree_contains(memory, child_id, node) {
+            return true;
+        }
+        child = memory.get_opt::<LayoutMemory>(child_id).and_then(|layout| layout.next);
+    }
+    false
+}
+
+impl UI<'_> {
+
+    pub fn shortcuts(&mut self) -> &mut Shortcuts {
+        self.memory.shortcuts()
+    }
+
+    /// Was a global shortcut triggered this frame?
+    /// While a node is focused, shortcuts without Ctrl/Cmd or Alt are ignored so that they do not fire while typing,
+    /// and chords claimed by a scoped shortcut around the focused node take precedence.
+    pub fn shortcut_pressed(&mut self, shortcut: &Shortcut) -> bool {
+        let input = self.input;
+        let focus = self.memory.get_focus();
+        let shortcuts = self.memory.shortcuts();
+        shortcuts.register(shortcut);
+        let Some(chord) = shortcuts.chord(shortcut) else { return false; };
+        if !chord.pressed(input) {
+            return false;
+        }
+        match focus {
+            Some(focus) => (chord.command || chord.alt) && shortcuts.claimed_chords.get(&chord) != Some(&focus),
+            None => true
+        }
+    }
+
+    /// Was a shortcut triggered this frame while the focused node is `scope` or inside of it?
+    pub fn scoped_shortcut_pressed(&mut self, shortcut: &Shortcut, scope: Id) -> bool {
+        let input = self.input;
+        let Some(focus) = self.memory.get_focus() else {
+            self.memory.shortcuts().register(shortcut);
+            return false;
+        };
+        let in_scope = subtree_contains(self.memory, scope, focus);
+        let shortcuts = self.memory.shortcuts();
+        shortcuts.register(shortcut);
+        let Some(chord) = shortcuts.chord(shortcut) else { return false; };
+        if !in_scope {
+            return false;
+        }
+        shortcuts.claimed_chords.retain(|_, claimed_focus| *claimed_focus == focus);
+        shortcuts.claimed_chords.insert(chord, focus);
+        chord.pressed(input)
+    }
+
+}
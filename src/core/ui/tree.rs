@@ -3,7 +3,7 @@ use std::fmt::Debug;
 
 use crate::{Axis, Color, Painter, PerAxis, Rect, Stroke, TSTransform, TextStyle, Vec2, Margin};
 
-use super::{Id, Layout, Size};
+use super::{Anchor, GridCell, Id, Layout, Size};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum UIRef {
@@ -37,10 +37,27 @@ impl UIRef {
 
 }
 
+/// What kind of widget a node represents.
+/// Used by tests and tooling to find nodes, does not affect the UI itself.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Role {
+    #[default]
+    None,
+    Label,
+    Button,
+    Checkbox,
+    TextField,
+    Dropdown,
+    MenuItem,
+    Tab
+}
+
 pub struct UINodeParams {
     // Layout
     pub(crate) size: PerAxis<Size>,
     pub(crate) layout: Layout,
+    pub(crate) grid_cell: GridCell,
+    pub(crate) anchor: Option<Anchor>,
     pub(crate) margin: Margin,
     pub(crate) interaction_margin: Margin,
     pub(crate) transform: TSTransform,
@@ -57,6 +74,7 @@ pub struct UINodeParams {
 
     // Id
     pub(crate) id_source: Option<u64>,
+    pub(crate) role: Role,
 
     // Input
     pub(crate) mouse: bool,
@@ -73,6 +91,8 @@ impl UINodeParams {
         Self {
             size: PerAxis::new(w, h),
             layout: Layout::new(Axis::Y),
+            grid_cell: GridCell::AUTO,
+            anchor: None,
             margin: Margin::ZERO,
             interaction_margin: Margin::ZERO,
             transform: TSTransform::IDENTITY,
@@ -83,6 +103,7 @@ impl UINodeParams {
             text: None,
             text_style: TextStyle::default(),
             id_source: None,
+            role: Role::None,
             mouse: false,
             scroll: false,
             has_interaction_priority: false,
@@ -105,6 +126,19 @@ impl UINodeParams {
         self
     }
 
+    /// Choose the cell the node is placed in when its parent has a grid layout
+    pub fn with_grid_cell(mut self, cell: GridCell) -> Self {
+        self.grid_cell = cell;
+        self
+    }
+
+    /// Take the node out of its parent's layout and place it against the parent's rect instead, e.g. for a badge or a playhead.
+    /// The node is still clipped by its parent and receives input like any other child.
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
     pub fn with_margin(mut self, margin: Margin) -> Self {
         self.margin = margin;
         self
@@ -154,6 +188,11 @@ impl UINodeParams {
         self
     }
 
+    pub fn with_role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+
     pub fn sense_mouse(mut self) -> Self {
         self.mouse = true;
         self
@@ -193,7 +232,15 @@ pub(crate) struct UINode {
     pub(crate) rect: Rect,
     pub(crate) transform: TSTransform,
     pub(crate) basis_size: Vec2,
-    pub(crate) frac_units: Vec2
+    pub(crate) frac_units: Vec2,
+
+    // grid layout
+    /// The sizes of the node's columns and rows, if it has a grid layout
+    pub(crate) grid_tracks: PerAxis<Vec<f32>>,
+    /// The sizes the node's columns and rows need to fit their children, which fractional tracks never shrink below
+    pub(crate) grid_content_tracks: PerAxis<Vec<f32>>,
+    /// The first column and row the node covers and how many of each it spans, if its parent has a grid layout
+    pub(crate) grid_placement: PerAxis<(usize, usize)>
 }
 
 impl UINode {
@@ -211,7 +258,10 @@ impl UINode {
             rect: Rect::ZERO,
             transform: TSTransform::IDENTITY,
             basis_size: Vec2::ZERO,
-            frac_units: Vec2::ONE
+            frac_units: Vec2::ONE,
+            grid_tracks: PerAxis::new(Vec::new(), Vec::new()),
+            grid_content_tracks: PerAxis::new(Vec::new(), Vec::new()),
+            grid_placement: PerAxis::splat((0, 1))
         }
     }
 
@@ -224,15 +274,23 @@ pub(crate) struct UITree {
     /// The root node of each layer of the UI.
     /// Layers cover the entire screen and are drawn in order, allowing for popups, context menus, etc.
     /// Each layer is its own tree of nodes, with the layer node being the root.
-    pub(crate) layers: Vec<UIRef>
+    pub(crate) layers: Vec<UIRef>,
+    /// The id the layer ids are derived from.
+    /// Each window has a different root id, so that nodes in different windows get different ids.
+    pub(crate) root_id: Id
 }
 
 impl UITree {
 
     pub(crate) fn new() -> Self {
+        Self::with_root(Id::ROOT)
+    }
+
+    pub(crate) fn with_root(root_id: Id) -> Self {
         Self {
             nodes: Vec::new(),
-            layers: Vec::new()
+            layers: Vec::new(),
+            root_id
         }
     }
 
@@ -257,7 +315,7 @@ impl UITree {
     
     pub(crate) fn add_layer(&mut self, size: Vec2) -> UIRef {
         let layer = self.add_node(UINode::new(
-            Id(0),
+            self.root_id,
             self.layers.len() as u64,
             UINodeParams::new(Size::px(size.x), Size::px(size.y)) 
         ));
@@ -0,0 +1,151 @@
+
+use std::collections::HashMap;
+
+use crate::{DisplayList, Input, IntegrationOutput, LayoutMemory, Painter, RawInput, TextRenderCache, Vec2, WindowConfig};
+
+use super::{FrameOutput, Id, UI};
+
+/// The state of a window opened with `UI::open_window`
+pub(crate) struct ChildWindow {
+    /// The config the window should be created with. Taken by the runtime when it creates the window
+    pub(crate) config: Option<WindowConfig>,
+    pub(crate) raw_input: RawInput,
+    /// The touch used as pen input in the window, if a pen is touching it
+    pub(crate) pen_touch: Option<u64>,
+    input: Input,
+
+    /// The size of the window in physical pixels. `None` until the runtime creates the window
+    pub(crate) physical_size: Option<Vec2>,
+    pub(crate) scale_factor: f32,
+
+    /// Was the window opened during the current frame?
+    pub(crate) open: bool,
+    /// Did the user ask to close the window?
+    pub(crate) close_requested: bool,
+    /// Did the runtime fail to create the window?
+    pub(crate) failed: bool,
+
+    /// Glyph layouts of the text painted in the window last frame
+    text_render_cache: TextRenderCache,
+    /// What was painted in the window during the last frame, waiting to be rendered
+    pub(crate) display_list: Option<DisplayList>,
+    pub(crate) output: Option<IntegrationOutput>
+}
+
+impl ChildWindow {
+
+    fn new(config: WindowConfig) -> Self {
+        Self {
+            config: Some(config),
+            raw_input: RawInput::new(),
+            pen_touch: None,
+            input: Input::new(),
+            physical_size: None,
+            scale_factor: 1.0,
+            open: false,
+            close_requested: false,
+            failed: false,
+            text_render_cache: TextRenderCache::new(),
+            display_list: None,
+            output: None
+        }
+    }
+
+}
+
+/// The windows opened with `UI::open_window`, keyed by the id they were opened with
+pub(crate) struct ChildWindows {
+    pub(crate) windows: HashMap<Id, ChildWindow>,
+    /// The time elapsed since the last frame
+    pub(crate) delta_time: f32
+}
+
+impl ChildWindows {
+
+    pub(crate) fn new() -> Self {
+        Self {
+            windows: HashMap::new(),
+            delta_time: 0.0
+        }
+    }
+
+    pub(crate) fn begin_frame(&mut self, delta_time: f32) {
+        self.delta_time = delta_time;
+        for window in self.windows.values_mut() {
+            window.open = false;
+        }
+    }
+
+    /// Forget the windows that were not opened during the last frame
+    pub(crate) fn close_unused(&mut self) {
+        self.windows.retain(|_, window| window.open);
+    }
+
+}
+
+impl UI<'_> {
+
+    /// Open a separate OS window, building its contents with `body`.
+    /// The window stays open as long as this is called every frame, and shares its memory with the rest of the UI.
+    /// The config is only used when the window is first created.
+    /// Returns false if the user asked to close the window, if the window could not be created,
+    /// or if the UI cannot open windows, e.g. when running headless.
+    pub fn open_window<F: FnOnce(&mut UI)>(&mut self, id: Id, config: WindowConfig, body: F) -> bool {
+        let Some(windows) = self.windows.as_deref_mut() else { return false; };
+        let delta_time = windows.delta_time;
+        let window = windows.windows.entry(id).or_insert_with(|| ChildWindow::new(config));
+        window.open = true;
+        if window.close_requested || window.failed {
+            return false;
+        }
+
+        // The runtime creates the window after this frame, so we need another frame to show its contents
+        let Some(physical_size) = window.physical_size else {
+            self.request_redraw = true;
+            return true;
+        };
+        let scale_factor = window.scale_factor;
+        let size = physical_size / scale_factor;
+
+        // distribute input
+        window.raw_input.delta_time = delta_time;
+        window.input.update(&mut window.raw_input, scale_factor);
+        window.input.distribute(self.memory, id);
+
+        // ui generation and layout
+        let mut ui = UI::new(
+            &window.input,
+            self.memory,
+            self.text_resources,
+            self.clipboard.as_deref_mut(),
+            self.device,
+            self.queue,
+            size
+        ).in_child_window(id);
+        body(&mut ui);
+        let FrameOutput { mut tree, request_redraw, cursor, request_ime } = ui.finish();
+
+        self.child_window_nodes.extend(tree.nodes.iter().map(|node| node.id));
+        if request_redraw {
+            self.request_redraw = true;
+        }
+
+        let ime_rect = request_ime.map(|ime_node| {
+            let id = tree.get(ime_node).id;
+            self.memory.get::<LayoutMemory>(id).screen_rect
+        });
+
+        // ui painting
+        let mut painter = Painter::new(self.text_resources, &mut window.text_render_cache, size, scale_factor);
+        tree.paint(&mut painter);
+        window.display_list = Some(painter.finish());
+        window.output = Some(IntegrationOutput {
+            request_redraw,
+            cursor,
+            ime_rect
+        });
+
+        true
+    }
+
+}
@@ -1,5 +1,5 @@
 
-use crate::{Color, Margin, Response, Size, TextStyle, UINodeParams, UIRef, UI};
+use crate::{Color, Margin, Response, Role, Size, TextStyle, UINodeParams, UIRef, UI};
 
 use super::{animate, icon_text_style, label_text_style, Theme};
 
@@ -30,6 +30,7 @@ pub fn button_with_text_style<S: Into<String>>(ui: &mut UI, label: S, style: Tex
             .with_text(label)
             .with_text_style(style)
             .with_rounding(rounding)
+            .with_role(Role::Button)
             .sense_mouse()
     );
 
@@ -1,7 +1,7 @@
 
-use crate::{icons, Color, Margin, Response, UI};
+use crate::{icons, Color, Layout, Margin, Response, Role, Size, UI};
 
-use super::{button_with_text_style, h_spacing, horizontal_fit, icon_text_style, label};
+use super::{button_with_text_style, container, icon_text_style, label};
 
 pub fn checkbox(ui: &mut UI, value: &mut bool) -> Response {
     let mut text_style = icon_text_style(ui);
@@ -10,6 +10,7 @@ pub fn checkbox(ui: &mut UI, value: &mut bool) -> Response {
     }
     let response = button_with_text_style(ui, icons::CHECK, text_style);
     ui.set_margin(response.node_ref, Margin::ZERO);
+    ui.set_role(response.node_ref, Role::Checkbox);
     if response.mouse_clicked() {
         *value = !*value;
     }
@@ -17,9 +18,8 @@ pub fn checkbox(ui: &mut UI, value: &mut bool) -> Response {
 }
 
 pub fn checkbox_labeled<S: Into<String>>(ui: &mut UI, label_text: S, value: &mut bool) -> Response {
-    horizontal_fit(ui, |ui| {
+    container(ui, Size::fit(), Size::fit(), Layout::horizontal().with_main_gap(5.0), |ui| {
         let response = checkbox(ui, value);
-        h_spacing(ui, 5.0);
         label(ui, label_text);
         response
     }).1
@@ -1,7 +1,7 @@
 
-use crate::{icons, Size, UINodeParams, UI};
+use crate::{icons, Layout, Margin, Size, UINodeParams, UI};
 
-use super::{h_spacing, horizontal_fit, icon, label, v_spacing, vertical_fit};
+use super::{icon, label};
 
 struct CollapsingHeaderMemory {
     open: bool
@@ -18,18 +18,22 @@ impl Default for CollapsingHeaderMemory {
 }
 
 pub fn collapsing_header<S: Into<String>, F: FnOnce(&mut UI)>(ui: &mut UI, label_text: S, contents: F) {
-    let container = ui.node(UINodeParams::new(Size::fit(), Size::fit()));
+    let container = ui.node(
+        UINodeParams::new(Size::fit(), Size::fit())
+            .with_layout(Layout::vertical().with_main_gap(5.0))
+    );
 
     ui.with_parent(container.node_ref, |ui| {
         let open = ui.memory().get::<CollapsingHeaderMemory>(container.id).open;
-        let (header_response, _) = horizontal_fit(ui, |ui| {
+        let header_params = UINodeParams::new(Size::fit(), Size::fit())
+            .with_layout(Layout::horizontal().with_main_gap(3.0));
+        let (header_response, _) = ui.with_node(header_params, |ui| {
             let icon_text = if open {
                 icons::CARET_DOWN
             } else {
                 icons::CARET_RIGHT
             };
             icon(ui, icon_text);
-            h_spacing(ui, 3.0);
             label(ui, label_text);
         });
         ui.set_sense_mouse(header_response.node_ref, true);
@@ -40,13 +44,13 @@ pub fn collapsing_header<S: Into<String>, F: FnOnce(&mut UI)>(ui: &mut UI, label
         }
 
         if open {
-            v_spacing(ui, 5.0);
-            horizontal_fit(ui, |ui| {
-                h_spacing(ui, 15.0);
-                vertical_fit(ui, |ui| {
-                    contents(ui);
-                });
-            });
+            // Indent the contents under the header
+            ui.with_node(
+                UINodeParams::new(Size::fit(), Size::fit())
+                    .with_layout(Layout::vertical())
+                    .with_margin(Margin::new(15.0, 0.0, 0.0, 0.0)),
+                contents
+            );
         }
 
     });
@@ -1,7 +1,8 @@
 
-use crate::Axis;
-use super::{DockingNodeId, DockingNodeKind, DockingTab, DockingTree};
+use crate::{Axis, Vec2};
+use super::{DockingNodeId, DockingNodeKind, DockingTab, DockingTree, FloatingWindow};
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub(super) struct TabDragSource {
     pub(super) node_id: DockingNodeId,
     pub(super) tab_idx: usize
@@ -10,7 +11,9 @@ pub(super) struct TabDragSource {
 pub(super) enum DockingCommand<Tab: DockingTab> {
     MoveTab {
         from: TabDragSource,
-        to: DockingNodeId
+        to: DockingNodeId,
+        /// Where to insert the tab among the tabs of `to`
+        idx: usize
     },
     AddTab {
         tab: Tab,
@@ -30,6 +33,35 @@ pub(super) enum DockingCommand<Tab: DockingTab> {
         child_idx: usize,
         amount: f32,
         min_size: f32
+    },
+    /// Move a tab into a new floating window
+    Float {
+        tab: TabDragSource,
+        position: Vec2,
+        size: Vec2
+    },
+    /// Move all the tabs of a floating window into a docked tabs node
+    Dock {
+        window: DockingNodeId,
+        to: DockingNodeId
+    },
+    /// Move a floating window, bringing it to the top
+    MoveFloating {
+        window: DockingNodeId,
+        amount: Vec2
+    },
+    ResizeFloating {
+        window: DockingNodeId,
+        amount: Vec2,
+        min_size: Vec2
+    },
+    /// Bring a floating window to the top
+    RaiseFloating {
+        window: DockingNodeId
+    },
+    /// Maximize a docked tabs node, or restore the docking area if it is already maximized
+    ToggleMaximized {
+        node_id: DockingNodeId
     }
 }
 
@@ -51,7 +83,7 @@ impl<Tab: DockingTab> DockingTree<Tab> {
             let parent_parent = self.get_parent(parent)?;
             self.delete_node(parent);
             if parent_parent.is_null() {
-                self.root = child;
+                self.replace_root(parent, child);
             } else {
                 let parent_parent_split = self.get_split_mut(parent_parent)?;
                 for (_, other) in &mut parent_parent_split.nodes {
@@ -66,7 +98,7 @@ impl<Tab: DockingTab> DockingTree<Tab> {
         Some(())
     }
 
-    fn take_tab(&mut self, tab_source: TabDragSource) -> Option<Tab> {
+    pub(super) fn take_tab(&mut self, tab_source: TabDragSource) -> Option<Tab> {
         let parent = self.get_parent(tab_source.node_id)?;
         let from_node = self.nodes.get_mut(&tab_source.node_id)?;
         let DockingNodeKind::Tabs(tabs) = &mut from_node.kind else { return None; }; 
@@ -75,48 +107,125 @@ impl<Tab: DockingTab> DockingTree<Tab> {
         }
 
         // Don't let us take the last tab from the root node 
-        if parent.is_null() && tabs.tabs.len() == 1 {
+        if tab_source.node_id == self.root && tabs.tabs.len() == 1 {
             return None;
         }
 
         let tab = tabs.tabs.remove(tab_source.tab_idx);
+        if tab_source.tab_idx < tabs.active_tab || tabs.active_tab >= tabs.tabs.len() {
+            tabs.active_tab = tabs.active_tab.saturating_sub(1);
+        }
 
         if tabs.tabs.is_empty() {
-            self.delete_tabs(tab_source.node_id);
+            if parent.is_null() { // The tabs were the only node in a floating window, so close the window
+                self.delete_node(tab_source.node_id);
+                self.floating.retain(|window| window.node != tab_source.node_id);
+            } else {
+                self.delete_tabs(tab_source.node_id);
+            }
         }
 
         Some(tab)
     }
 
-    fn move_tab(&mut self, from: TabDragSource, to: DockingNodeId) -> Option<()> {
-        if from.node_id == to && self.get_tabs(to)?.tabs.len() == 1 {
+    /// Move a tab so that it is inserted at `idx` among the tabs of `to`, and make it active
+    pub(super) fn move_tab(&mut self, from: TabDragSource, to: DockingNodeId, idx: usize) -> Option<()> {
+        if from.node_id == to { // Reordering tabs within the same node
+            let tabs = self.get_tabs_mut(to)?;
+            if from.tab_idx >= tabs.tabs.len() {
+                return None;
+            }
+            let tab = tabs.tabs.remove(from.tab_idx);
+            // Removing the tab shifts the tabs after it back by one
+            let idx = if idx > from.tab_idx { idx - 1 } else { idx }.min(tabs.tabs.len());
+            tabs.tabs.insert(idx, tab);
+            tabs.active_tab = idx;
             return Some(());
         }
+
+        // `to` is a different tabs node, so taking the tab does not remove it
+        self.get_tabs(to)?;
         let tab = self.take_tab(from)?;
         let to_tabs = self.get_tabs_mut(to)?;
-        to_tabs.tabs.push(tab);
+        let idx = idx.min(to_tabs.tabs.len());
+        to_tabs.tabs.insert(idx, tab);
+        to_tabs.active_tab = idx;
         Some(())
     }
 
-    fn add_tab(&mut self, tab: Tab, to: DockingNodeId) -> Option<()> {
+    pub(super) fn add_tab(&mut self, tab: Tab, to: DockingNodeId) -> Option<()> {
         let to_tabs = self.get_tabs_mut(to)?;
         to_tabs.tabs.push(tab);
         Some(())
     }
 
-    fn split(&mut self, tab: TabDragSource, to: DockingNodeId, direction: Axis, max: bool) -> Option<()> {
-        if tab.node_id == to && self.get_tabs(to)?.tabs.len() == 1 {
-            return Some(());
+    /// Put a tab back where it was taken from, e.g. when it could not be moved.
+    /// If taking the tab removed its node, the tab goes to the first tabs node of the docking tree instead.
+    fn restore_tab(&mut self, tab: Tab, from: TabDragSource) {
+        let (node_id, idx) = match self.get_tabs(from.node_id) {
+            Some(tabs) => (from.node_id, from.tab_idx.min(tabs.tabs.len())),
+            None => match self.first_tabs(self.root) {
+                Some(node_id) => (node_id, usize::MAX),
+                None => return
+            }
+        };
+        let Some(tabs) = self.get_tabs_mut(node_id) else { return; };
+        let idx = idx.min(tabs.tabs.len());
+        tabs.tabs.insert(idx, tab);
+        tabs.active_tab = idx;
+    }
+
+    /// Move a tab into a new tabs node next to `to`, returning the node the tab ends up in
+    pub(super) fn split(&mut self, from: TabDragSource, to: DockingNodeId, direction: Axis, max: bool) -> Option<DockingNodeId> {
+        // Check that both the tab and the target exist before taking the tab out of the tree
+        self.get_tabs(from.node_id)?.tabs.get(from.tab_idx)?;
+        self.first_tabs(to)?;
+        if !self.can_split_next_to(to) {
+            return None;
+        }
+        if from.node_id == to && self.get_tabs(to)?.tabs.len() == 1 {
+            return Some(to);
         }
 
-        let tab = self.take_tab(tab)?;
-        let tabs = self.add_tabs(DockingNodeId::NULL, vec![tab]);
+        // Taking the tab might collapse `to` if it is a split, replacing it with its remaining child
+        let to_children: Vec<DockingNodeId> = self.get_split(to).map(|split| split.nodes.iter().map(|(_, id)| *id).collect()).unwrap_or_default();
+        let tab = self.take_tab(from)?;
+        let to = if self.get(to).is_some() {
+            to
+        } else {
+            to_children.into_iter().find(|child| self.get(*child).is_some()).unwrap_or(to)
+        };
+
+        if !self.can_split_next_to(to) {
+            self.restore_tab(tab, from);
+            return None;
+        }
+        self.split_with_tab(tab, to, direction, max)
+    }
+
+    /// Can a new node be put next to `to`? True for the roots of the tree and floating windows, and for the children of splits.
+    fn can_split_next_to(&self, to: DockingNodeId) -> bool {
+        match self.get_parent(to) {
+            Some(parent) if parent.is_null() => true,
+            Some(parent) => self.get_split(parent).is_some_and(|split| split.nodes.iter().any(|(_, id)| *id == to)),
+            None => false
+        }
+    }
 
+    /// Put a tab in a new tabs node next to `to`, returning the new node
+    pub(super) fn split_with_tab(&mut self, tab: Tab, to: DockingNodeId, direction: Axis, max: bool) -> Option<DockingNodeId> {
+        // Check the target before the tab is put in a node, so that the tab is not lost if it is invalid
+        if !self.can_split_next_to(to) {
+            return None;
+        }
         let to_parent = self.get_parent(to)?;
+        // Show the new node, even if it is next to the maximized node
+        self.maximized = None;
+        let tabs = self.add_tabs(DockingNodeId::NULL, vec![tab]);
 
-        if to_parent.is_null() { // Case 1: the tabs node we're splitting is the root of the docking tree
+        if to_parent.is_null() { // Case 1: the tabs node we're splitting is the root of the docking tree or of a floating window
             let new_root = self.add_split(DockingNodeId::NULL, direction);
-            self.root = new_root;
+            self.replace_root(to, new_root);
             self.set_parent(to, new_root);
             self.set_parent(tabs, new_root);
             let split = self.get_split_mut(new_root)?;
@@ -160,7 +269,7 @@ impl<Tab: DockingTab> DockingTree<Tab> {
             self.set_parent(tabs, to_parent);
         }
         
-        Some(())
+        Some(tabs)
     }
 
     fn move_split(&mut self, node_id: DockingNodeId, child_idx: usize, amount: f32, min_size: f32) -> Option<()> {
@@ -186,10 +295,119 @@ impl<Tab: DockingTab> DockingTree<Tab> {
         Some(())
     }
 
+    fn float(&mut self, tab: TabDragSource, position: Vec2, size: Vec2) -> Option<()> {
+        let tab = self.take_tab(tab)?;
+        let node = self.add_tabs(DockingNodeId::NULL, vec![tab]);
+        self.floating.push(FloatingWindow {
+            node,
+            position,
+            size
+        });
+        Some(())
+    }
+
+    /// Remove a node and all of its descendants, collecting their tabs
+    fn take_subtree_tabs(&mut self, id: DockingNodeId, tabs: &mut Vec<Tab>) -> Option<()> {
+        let node = self.nodes.remove(&id)?;
+        match node.kind {
+            DockingNodeKind::Tabs(node_tabs) => {
+                tabs.extend(node_tabs.tabs);
+            },
+            DockingNodeKind::Split(split) => {
+                for (_, child) in split.nodes {
+                    self.take_subtree_tabs(child, tabs);
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn dock(&mut self, window: DockingNodeId, to: DockingNodeId) -> Option<()> {
+        let window_idx = self.get_floating_idx(window)?;
+        self.get_tabs(to)?;
+
+        // Make sure we're not docking the window into itself
+        let mut to_root = to;
+        while !self.get_parent(to_root)?.is_null() {
+            to_root = self.get_parent(to_root)?;
+        }
+        if to_root == window {
+            return None;
+        }
+
+        self.floating.remove(window_idx);
+        let mut tabs = Vec::new();
+        self.take_subtree_tabs(window, &mut tabs);
+        self.get_tabs_mut(to)?.tabs.extend(tabs);
+        Some(())
+    }
+
+    pub(super) fn raise_floating(&mut self, window: DockingNodeId) -> Option<()> {
+        let window_idx = self.get_floating_idx(window)?;
+        let window = self.floating.remove(window_idx);
+        self.floating.push(window);
+        Some(())
+    }
+
+    fn move_floating(&mut self, window: DockingNodeId, amount: Vec2) -> Option<()> {
+        let window_idx = self.get_floating_idx(window)?;
+        self.floating[window_idx].position += amount;
+        self.raise_floating(window)
+    }
+
+    fn resize_floating(&mut self, window: DockingNodeId, amount: Vec2, min_size: Vec2) -> Option<()> {
+        let window_idx = self.get_floating_idx(window)?;
+        let window = &mut self.floating[window_idx];
+        window.size = (window.size + amount).max(min_size);
+        Some(())
+    }
+
+    pub(super) fn maximize(&mut self, node_id: DockingNodeId) -> Option<()> {
+        self.get_tabs(node_id)?;
+        if self.get_root(node_id)? != self.root {
+            return None;
+        }
+        self.maximized = Some(node_id);
+        Some(())
+    }
+
+    fn toggle_maximized(&mut self, node_id: DockingNodeId) -> Option<()> {
+        if self.maximized == Some(node_id) {
+            self.maximized = None;
+            return Some(());
+        }
+        self.maximize(node_id)
+    }
+
+    /// Execute the commands collected during a frame, in order.
+    /// Closing a tab shifts the indices of the tabs after it, so each run of consecutive closes is applied
+    /// from the last tab of each node to the first, and tabs closed several times in a run are only closed once.
+    pub(super) fn execute_commands(&mut self, commands: Vec<DockingCommand<Tab>>) {
+        let mut closed_tabs = Vec::new();
+        for command in commands {
+            match command {
+                DockingCommand::CloseTab { tab } => closed_tabs.push(tab),
+                command => {
+                    self.close_tabs_from_last(&mut closed_tabs);
+                    self.execute_command(command);
+                }
+            }
+        }
+        self.close_tabs_from_last(&mut closed_tabs);
+    }
+
+    fn close_tabs_from_last(&mut self, tabs: &mut Vec<TabDragSource>) {
+        tabs.sort_by_key(|tab| (tab.node_id.0, std::cmp::Reverse(tab.tab_idx)));
+        tabs.dedup();
+        for tab in tabs.drain(..) {
+            self.take_tab(tab);
+        }
+    }
+
     pub(super) fn execute_command(&mut self, command: DockingCommand<Tab>) {
         match command {
-            DockingCommand::MoveTab { from, to } => {
-                self.move_tab(from, to);
+            DockingCommand::MoveTab { from, to, idx } => {
+                self.move_tab(from, to, idx);
             },
             DockingCommand::AddTab { tab, to } => {
                 self.add_tab(tab, to);
@@ -202,6 +420,24 @@ impl<Tab: DockingTab> DockingTree<Tab> {
             },
             DockingCommand::MoveSplit { node_id, child_idx, amount, min_size } => {
                 self.move_split(node_id, child_idx, amount, min_size);
+            },
+            DockingCommand::Float { tab, position, size } => {
+                self.float(tab, position, size);
+            },
+            DockingCommand::Dock { window, to } => {
+                self.dock(window, to);
+            },
+            DockingCommand::MoveFloating { window, amount } => {
+                self.move_floating(window, amount);
+            },
+            DockingCommand::ResizeFloating { window, amount, min_size } => {
+                self.resize_floating(window, amount, min_size);
+            },
+            DockingCommand::RaiseFloating { window } => {
+                self.raise_floating(window);
+            },
+            DockingCommand::ToggleMaximized { node_id } => {
+                self.toggle_maximized(node_id);
             }
         }
     }
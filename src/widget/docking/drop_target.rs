@@ -0,0 +1,123 @@
+
+use crate::{vec2, Axis, PaintRect, Rect, Stroke, Theme, Vec2, UI};
+
+/// The size of the buttons in the drop overlay
+const BUTTON_SIZE: f32 = 32.0;
+/// The space between the buttons of the drop overlay, and between the edge buttons and the edges of the docking area
+const BUTTON_SPACING: f32 = 6.0;
+
+/// A place to drop a dragged tab, relative to a docking node
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum DropTarget {
+    Center,
+    Left,
+    Right,
+    Top,
+    Bottom
+}
+
+impl DropTarget {
+
+    pub(super) const ALL: [Self; 5] = [Self::Center, Self::Left, Self::Right, Self::Top, Self::Bottom];
+    pub(super) const EDGES: [Self; 4] = [Self::Left, Self::Right, Self::Top, Self::Bottom];
+
+    /// The direction of the split created by dropping a tab on the target, and whether the tab goes after the existing node.
+    /// `None` for the center, where the tab is added to the existing node.
+    pub(super) fn split(self) -> Option<(Axis, bool)> {
+        match self {
+            Self::Center => None,
+            Self::Left => Some((Axis::X, false)),
+            Self::Right => Some((Axis::X, true)),
+            Self::Top => Some((Axis::Y, false)),
+            Self::Bottom => Some((Axis::Y, true))
+        }
+    }
+
+    /// The part of `rect` that a tab dropped on the target ends up taking
+    pub(super) fn preview_rect(self, rect: Rect) -> Rect {
+        match self {
+            Self::Center => rect,
+            Self::Left => rect.left_half(),
+            Self::Right => rect.right_half(),
+            Self::Top => rect.top_half(),
+            Self::Bottom => rect.bottom_half()
+        }
+    }
+
+    fn direction(self) -> Vec2 {
+        match self {
+            Self::Center => Vec2::ZERO,
+            Self::Left => vec2(-1.0, 0.0),
+            Self::Right => vec2(1.0, 0.0),
+            Self::Top => vec2(0.0, -1.0),
+            Self::Bottom => vec2(0.0, 1.0)
+        }
+    }
+
+    fn button_rect_at(center: Vec2) -> Rect {
+        Rect::min_size(center - Vec2::splat(BUTTON_SIZE / 2.0), Vec2::splat(BUTTON_SIZE))
+    }
+
+    /// The button for the target in the cross of buttons at the center of `rect`
+    pub(super) fn center_button_rect(self, rect: Rect) -> Rect {
+        Self::button_rect_at(rect.center() + self.direction() * (BUTTON_SIZE + BUTTON_SPACING))
+    }
+
+    /// The button for the target on the matching edge of `rect`
+    pub(super) fn edge_button_rect(self, rect: Rect) -> Rect {
+        let offset = (rect.size() / 2.0 - Vec2::splat(BUTTON_SIZE / 2.0 + BUTTON_SPACING)).max(Vec2::ZERO);
+        Self::button_rect_at(rect.center() + self.direction() * offset)
+    }
+
+}
+
+/// The buttons of a drop overlay along with the target the mouse is over, if any
+pub(super) struct DropOverlay {
+    buttons: Vec<(DropTarget, Rect)>,
+    pub(super) hovered: Option<DropTarget>
+}
+
+impl DropOverlay {
+
+    pub(super) fn new<I: IntoIterator<Item = DropTarget>, F: Fn(DropTarget) -> Rect>(targets: I, button_rect: F, mouse_pos: Vec2) -> Self {
+        let buttons: Vec<_> = targets.into_iter().map(|target| (target, button_rect(target))).collect();
+        let hovered = buttons.iter().find(|(_, rect)| rect.contains(mouse_pos)).map(|(target, _)| *target);
+        Self {
+            buttons,
+            hovered
+        }
+    }
+
+    /// Draw the buttons above everything else, along with a preview of the part of `rect` the tab would take up if dropped on the hovered target
+    pub(super) fn render(self, ui: &mut UI, rect: Rect) {
+        let theme = ui.style::<Theme>();
+        let button_fill = theme.bg_popup;
+        let button_stroke = theme.widget_stroke();
+        let rounding = theme.widget_rounding;
+        let icon_color = theme.text;
+        let accent = theme.accent;
+
+        let preview = self.hovered.map(|target| target.preview_rect(rect));
+        let (layer, _) = ui.layer(|_| {});
+        ui.set_on_paint(layer, move |painter, layer_rect| {
+            let offset = layer_rect.tl();
+            if let Some(preview) = preview {
+                painter.rect(
+                    PaintRect::new(preview.shift(offset), accent.with_alpha(0.25))
+                        .with_stroke(Stroke::new(accent, 2.0))
+                );
+            }
+            for (target, button) in self.buttons {
+                let button = button.shift(offset);
+                let fill = if self.hovered == Some(target) { accent } else { button_fill };
+                painter.rect(PaintRect::new(button, fill).with_stroke(button_stroke).with_rounding(rounding));
+
+                // A small picture of where the tab ends up
+                let icon = Rect::min_size(button.tl() + Vec2::splat(7.0), button.size() - Vec2::splat(14.0));
+                painter.rect(PaintRect::new(icon, icon_color.with_alpha(0.15)).with_stroke(Stroke::new(icon_color, 1.0)));
+                painter.rect(PaintRect::new(target.preview_rect(icon), icon_color.with_alpha(0.6)));
+            }
+        });
+    }
+
+}
@@ -0,0 +1,148 @@
+
+use crate::{Axis, Vec2};
+
+use super::{DockingNode, DockingNodeId, DockingNodeKind, DockingState, DockingTab, DockingTree, FloatingWindow, Split, Tabs};
+
+/// A node in a saved docking layout, with each tab replaced by a persistent key
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DockingLayoutNode<K> {
+    Tabs {
+        tabs: Vec<K>,
+        active_tab: usize
+    },
+    Split {
+        direction: Axis,
+        /// The children of the split, along with the fraction of the split's size each one takes up
+        nodes: Vec<(f32, DockingLayoutNode<K>)>
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FloatingWindowLayout<K> {
+    pub node: DockingLayoutNode<K>,
+    pub position: Vec2,
+    pub size: Vec2
+}
+
+/// A saved arrangement of docking tabs, which can be stored between runs or used as a workspace preset.
+/// Enable the `serde` feature to serialize it.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DockingLayout<K> {
+    pub root: DockingLayoutNode<K>,
+    pub floating: Vec<FloatingWindowLayout<K>>
+}
+
+impl<Tab: DockingTab> DockingTree<Tab> {
+
+    fn save_node<K, F: FnMut(&Tab) -> K>(&self, id: DockingNodeId, key: &mut F) -> Option<DockingLayoutNode<K>> {
+        match &self.get(id)?.kind {
+            DockingNodeKind::Tabs(tabs) => Some(DockingLayoutNode::Tabs {
+                tabs: tabs.tabs.iter().map(&mut *key).collect(),
+                active_tab: tabs.active_tab
+            }),
+            DockingNodeKind::Split(split) => Some(DockingLayoutNode::Split {
+                direction: split.direction,
+                nodes: split.nodes.iter()
+                    .filter_map(|(size, child)| Some((*size, self.save_node(*child, key)?)))
+                    .collect()
+            })
+        }
+    }
+
+    /// Add the nodes of a saved layout to the tree.
+    /// Tabs that cannot be restored are skipped, and nodes left without any tabs are removed.
+    fn load_node<K, F: FnMut(&K) -> Option<Tab>>(&mut self, parent: DockingNodeId, node: &DockingLayoutNode<K>, tab: &mut F) -> Option<DockingNodeId> {
+        match node {
+            DockingLayoutNode::Tabs { tabs, active_tab } => {
+                let tabs: Vec<Tab> = tabs.iter().filter_map(&mut *tab).collect();
+                if tabs.is_empty() {
+                    return None;
+                }
+                let active_tab = (*active_tab).min(tabs.len() - 1);
+                Some(self.add_node(DockingNode {
+                    parent,
+                    kind: DockingNodeKind::Tabs(Tabs {
+                        active_tab,
+                        ..Tabs::new(tabs)
+                    })
+                }))
+            },
+            DockingLayoutNode::Split { direction, nodes } => {
+                let split = self.add_split(parent, *direction);
+                let children: Vec<(f32, DockingNodeId)> = nodes.iter()
+                    .filter_map(|(size, child)| Some((*size, self.load_node(split, child, tab)?)))
+                    .collect();
+
+                // Splits with fewer than two children are replaced by their only child, if there is one
+                if children.len() < 2 {
+                    self.delete_node(split);
+                    let (_, child) = *children.first()?;
+                    self.set_parent(child, parent);
+                    return Some(child);
+                }
+
+                self.get_mut(split)?.kind = DockingNodeKind::Split(Split {
+                    nodes: children,
+                    direction: *direction
+                });
+                Some(split)
+            }
+        }
+    }
+
+}
+
+impl<Tab: DockingTab> DockingState<Tab> {
+
+    /// Save the arrangement of the tabs, using `key` to turn each tab into something that can be stored
+    pub fn save_layout<K, F: FnMut(&Tab) -> K>(&self, mut key: F) -> DockingLayout<K> {
+        let tree = &self.tree;
+        DockingLayout {
+            root: tree.save_node(tree.root, &mut key).unwrap_or(DockingLayoutNode::Tabs { tabs: Vec::new(), active_tab: 0 }),
+            floating: tree.floating.iter()
+                .filter_map(|window| Some(FloatingWindowLayout {
+                    node: tree.save_node(window.node, &mut key)?,
+                    position: window.position,
+                    size: window.size
+                }))
+                .collect()
+        }
+    }
+
+    /// Restore a saved arrangement of tabs, using `tab` to recreate each tab from its key.
+    /// Tabs for which `tab` returns `None` are left out.
+    pub fn from_layout<K, F: FnMut(&K) -> Option<Tab>>(layout: &DockingLayout<K>, mut tab: F) -> Self {
+        let mut tree = DockingTree {
+            nodes: Default::default(),
+            curr_id: 0,
+            root: DockingNodeId::NULL,
+            floating: Vec::new(),
+            maximized: None
+        };
+
+        tree.root = tree.load_node(DockingNodeId::NULL, &layout.root, &mut tab)
+            .unwrap_or_else(|| tree.add_tabs(DockingNodeId::NULL, Vec::new()));
+        for window in &layout.floating {
+            if let Some(node) = tree.load_node(DockingNodeId::NULL, &window.node, &mut tab) {
+                tree.floating.push(FloatingWindow {
+                    node,
+                    position: window.position,
+                    size: window.size
+                });
+            }
+        }
+
+        Self {
+            tree
+        }
+    }
+
+    /// Replace the arrangement of the tabs with a saved one, e.g. to switch to a workspace preset
+    pub fn load_layout<K, F: FnMut(&K) -> Option<Tab>>(&mut self, layout: &DockingLayout<K>, tab: F) {
+        *self = Self::from_layout(layout, tab);
+    }
+
+}
@@ -1,10 +1,17 @@
 
 mod render;
 mod command;
+mod drop_target;
+mod layout;
+#[cfg(test)]
+mod tests;
+pub use layout::*;
 
 use std::{collections::HashMap, usize};
 
-use crate::{Axis, UI};
+use command::TabDragSource;
+
+use crate::{Axis, Rect, Vec2, UI};
 
 pub trait DockingTab: Sized {
 
@@ -14,12 +21,51 @@ pub trait DockingTab: Sized {
     fn render(&mut self, ui: &mut UI, context: &mut Self::Context);
 
     fn add_tab_dropdown<F: FnMut(Self)>(ui: &mut UI, add_tab: F, context: &mut Self::Context);
+
+    /// An icon from `pierro::icons` to show before the title
+    fn icon(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Text shown when hovering over the tab
+    fn tooltip(&self) -> Option<String> {
+        None
+    }
+
+    /// Can the user close the tab?
+    fn closable(&self) -> bool {
+        true
+    }
+
+    /// Does the tab have unsaved changes? Marked with a dot in place of the close button.
+    fn modified(&self) -> bool {
+        false
+    }
+
+    /// Called when the user tries to close the tab.
+    /// Return false to keep the tab open, e.g. to ask about unsaved changes first, and close it later using `wants_to_close`.
+    fn confirm_close(&mut self, _context: &mut Self::Context) -> bool {
+        true
+    }
+
+    /// Checked every frame. Return true to close the tab, e.g. once the user has confirmed that it should be closed.
+    fn wants_to_close(&self) -> bool {
+        false
+    }
+
+    /// Add entries to the end of the tab's context menu, after the built-in docking actions.
+    /// Return true when one of the entries is clicked to close the menu.
+    fn context_menu(&mut self, _ui: &mut UI, _context: &mut Self::Context) -> bool {
+        false
+    }
     
 }
 
 struct Tabs<Tab: DockingTab> {
     tabs: Vec<Tab>,
-    active_tab: usize
+    active_tab: usize,
+    /// The screen-space rect of the active tab's contents in the last frame
+    content_rect: Rect
 }
 
 impl<Tab:DockingTab> Tabs<Tab> {
@@ -28,13 +74,15 @@ impl<Tab:DockingTab> Tabs<Tab> {
         Self {
             tabs: tabs,
             active_tab: 0,
+            content_rect: Rect::ZERO
         }
     }
 
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
-struct DockingNodeId(usize);
+/// Identifies a node in a `DockingState`, either a group of tabs or a split
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DockingNodeId(usize);
 
 impl DockingNodeId {
 
@@ -61,10 +109,23 @@ struct DockingNode<Tab: DockingTab> {
     kind: DockingNodeKind<Tab>
 }
 
+/// A docking node rendered in a panel above the rest of the docking tree
+struct FloatingWindow {
+    /// The root of the window's docking nodes. Its parent is null, just like the docking tree's root
+    node: DockingNodeId,
+    /// The screen-space position of the top-left corner of the window
+    position: Vec2,
+    size: Vec2
+}
+
 struct DockingTree<Tab: DockingTab> {
     nodes: HashMap<DockingNodeId, DockingNode<Tab>>,
     curr_id: usize,
-    root: DockingNodeId 
+    root: DockingNodeId,
+    /// The floating windows, from bottom to top
+    floating: Vec<FloatingWindow>,
+    /// A docked tabs node temporarily filling the whole docking area in place of the root
+    maximized: Option<DockingNodeId>
 }
 
 impl<Tab: DockingTab> DockingTree<Tab> {
@@ -79,7 +140,9 @@ impl<Tab: DockingTab> DockingTree<Tab> {
         Self {
             nodes,
             curr_id: 1,
-            root
+            root,
+            floating: Vec::new(),
+            maximized: None
         }
     }
 
@@ -160,12 +223,103 @@ impl<Tab: DockingTab> DockingTree<Tab> {
         Some(())
     } 
 
+    fn get_floating_idx(&self, id: DockingNodeId) -> Option<usize> {
+        self.floating.iter().position(|window| window.node == id)
+    }
+
+    /// Replace a node with a null parent, either the root of the tree or of a floating window, with another node
+    fn replace_root(&mut self, old: DockingNodeId, new: DockingNodeId) {
+        if self.root == old {
+            self.root = new;
+        }
+        for window in &mut self.floating {
+            if window.node == old {
+                window.node = new;
+            }
+        }
+    }
+
 }
 
 pub struct DockingState<Tab: DockingTab> {
     tree: DockingTree<Tab>
 }
 
+/// The location of a tab in a `DockingState`.
+/// Only valid until the tabs are rearranged.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DockingTabRef {
+    pub node: DockingNodeId,
+    pub tab_idx: usize
+}
+
+impl From<DockingTabRef> for TabDragSource {
+
+    fn from(tab: DockingTabRef) -> Self {
+        Self {
+            node_id: tab.node,
+            tab_idx: tab.tab_idx
+        }
+    }
+
+}
+
+impl<Tab: DockingTab> DockingTree<Tab> {
+
+    /// Is a node hidden because a node outside of it is maximized?
+    fn is_hidden(&self, id: DockingNodeId) -> bool {
+        let Some(mut maximized) = self.maximized else { return false; };
+        loop {
+            if maximized == id {
+                return false;
+            }
+            match self.get_parent(maximized) {
+                Some(parent) if !parent.is_null() => maximized = parent,
+                _ => return true
+            }
+        }
+    }
+
+    /// The root of the tree or floating window a node is in
+    fn get_root(&self, mut id: DockingNodeId) -> Option<DockingNodeId> {
+        while !self.get_parent(id)?.is_null() {
+            id = self.get_parent(id)?;
+        }
+        Some(id)
+    }
+
+    /// Find the first tabs node in a node, going through splits
+    fn first_tabs(&self, id: DockingNodeId) -> Option<DockingNodeId> {
+        match &self.get(id)?.kind {
+            DockingNodeKind::Tabs(_) => Some(id),
+            DockingNodeKind::Split(split) => self.first_tabs(split.nodes.first()?.1)
+        }
+    }
+
+    /// The tabs nodes in the tree, followed by those in floating windows
+    fn all_tabs(&self) -> Vec<DockingNodeId> {
+        fn collect<Tab: DockingTab>(tree: &DockingTree<Tab>, id: DockingNodeId, tabs: &mut Vec<DockingNodeId>) {
+            match tree.get(id).map(|node| &node.kind) {
+                Some(DockingNodeKind::Tabs(_)) => tabs.push(id),
+                Some(DockingNodeKind::Split(split)) => {
+                    for (_, child) in &split.nodes {
+                        collect(tree, *child, tabs);
+                    }
+                },
+                None => {}
+            }
+        }
+
+        let mut tabs = Vec::new();
+        collect(self, self.root, &mut tabs);
+        for window in &self.floating {
+            collect(self, window.node, &mut tabs);
+        }
+        tabs
+    }
+
+}
+
 impl<Tab: DockingTab> DockingState<Tab> {
 
     pub fn new(tabs: Vec<Tab>) -> Self {
@@ -174,4 +328,112 @@ impl<Tab: DockingTab> DockingState<Tab> {
         }
     }
 
+    /// The root node of the docked tabs
+    pub fn root(&self) -> DockingNodeId {
+        self.tree.root
+    }
+
+    /// Iterate over all the tabs, both docked and floating
+    pub fn tabs(&self) -> impl Iterator<Item = (DockingTabRef, &Tab)> {
+        self.tree.all_tabs().into_iter().flat_map(move |node| {
+            self.tree.get_tabs(node).into_iter().flat_map(move |tabs| {
+                tabs.tabs.iter().enumerate().map(move |(tab_idx, tab)| (DockingTabRef { node, tab_idx }, tab))
+            })
+        })
+    }
+
+    /// Find the first tab matching a predicate
+    pub fn find_tab<P: Fn(&Tab) -> bool>(&self, predicate: P) -> Option<DockingTabRef> {
+        self.tabs().find(|(_, tab)| predicate(tab)).map(|(tab_ref, _)| tab_ref)
+    }
+
+    pub fn get_tab(&self, tab: DockingTabRef) -> Option<&Tab> {
+        self.tree.get_tabs(tab.node)?.tabs.get(tab.tab_idx)
+    }
+
+    pub fn get_tab_mut(&mut self, tab: DockingTabRef) -> Option<&mut Tab> {
+        self.tree.get_tabs_mut(tab.node)?.tabs.get_mut(tab.tab_idx)
+    }
+
+    /// Make a tab the active tab of its node, bringing its floating window to the top if it has one.
+    /// Returns false if the tab does not exist.
+    pub fn focus_tab(&mut self, tab: DockingTabRef) -> bool {
+        let Some(tabs) = self.tree.get_tabs_mut(tab.node) else { return false; };
+        if tab.tab_idx >= tabs.tabs.len() {
+            return false;
+        }
+        tabs.active_tab = tab.tab_idx;
+        if let Some(root) = self.tree.get_root(tab.node) {
+            self.tree.raise_floating(root);
+        }
+        true
+    }
+
+    /// Add a tab to a node and make it active.
+    /// If the node is a split, the tab is added to the first group of tabs inside it.
+    pub fn add_tab(&mut self, tab: Tab, to: DockingNodeId) -> Option<DockingTabRef> {
+        let node = self.tree.first_tabs(to)?;
+        self.tree.add_tab(tab, node)?;
+        let tab_idx = self.tree.get_tabs(node)?.tabs.len() - 1;
+        let tab = DockingTabRef { node, tab_idx };
+        self.focus_tab(tab);
+        Some(tab)
+    }
+
+    /// Add a tab in a new node next to an existing one.
+    /// If `max` is true, the new node is placed after the existing one, to the right or below it.
+    pub fn add_tab_split(&mut self, tab: Tab, next_to: DockingNodeId, direction: Axis, max: bool) -> Option<DockingTabRef> {
+        let node = self.tree.split_with_tab(tab, next_to, direction, max)?;
+        Some(DockingTabRef { node, tab_idx: 0 })
+    }
+
+    /// Move a tab to the end of another node's tabs and make it active
+    pub fn move_tab(&mut self, tab: DockingTabRef, to: DockingNodeId) {
+        if let Some(to) = self.tree.first_tabs(to) {
+            self.tree.move_tab(tab.into(), to, usize::MAX);
+        }
+    }
+
+    /// Move a tab into a new node next to an existing one, returning where the tab ends up.
+    /// If `max` is true, the new node is placed after the existing one, to the right or below it.
+    /// Returns `None` and leaves the tab where it was if either the tab or the node does not exist.
+    pub fn split(&mut self, tab: DockingTabRef, next_to: DockingNodeId, direction: Axis, max: bool) -> Option<DockingTabRef> {
+        let node = self.tree.split(tab.into(), next_to, direction, max)?;
+        Some(DockingTabRef { node, tab_idx: 0 })
+    }
+
+    /// Make a docked tabs node fill the whole docking area, hiding the other docked nodes until `restore` is called.
+    /// Returns false if the node is not a docked tabs node.
+    pub fn maximize(&mut self, node: DockingNodeId) -> bool {
+        self.tree.maximize(node).is_some()
+    }
+
+    /// Show all the docked nodes again after a node was maximized
+    pub fn restore(&mut self) {
+        self.tree.maximized = None;
+    }
+
+    /// The currently maximized node, if there is one
+    pub fn maximized(&self) -> Option<DockingNodeId> {
+        self.tree.maximized
+    }
+
+    /// Close a tab without calling `DockingTab::confirm_close`, returning it.
+    /// The last tab in the root node cannot be closed.
+    pub fn close_tab(&mut self, tab: DockingTabRef) -> Option<Tab> {
+        self.tree.take_tab(tab.into())
+    }
+
+    /// Close all tabs matching a predicate, except for the last tab in the root node
+    pub fn close_tabs<P: Fn(&Tab) -> bool>(&mut self, predicate: P) {
+        // Closing a tab invalidates the other tab references, so look them up one at a time
+        let mut skip = 0;
+        loop {
+            let Some((tab, _)) = self.tabs().filter(|(_, tab)| predicate(tab)).nth(skip) else { break; };
+            if self.close_tab(tab).is_none() {
+                skip += 1;
+            }
+        }
+    }
+
 }
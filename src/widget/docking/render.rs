@@ -1,17 +1,34 @@
 
-use crate::{button_fill_animation, dnd_drop_zone_with_size, dnd_source, h_draggable_line, horizontal_fit, icon_text_style, icons, left_click_context_menu, menu_bar, tab, v_draggable_line, v_line, Axis, Color, CursorIcon, Layout, LayoutInfo, Margin, PaintRect, PerAxis, ScrollArea, Size, Stroke, Theme, UINodeParams, UI};
+use crate::{button_fill_animation, close_context_menu, context_menu, dnd_source, h_line, menu_button, tab_with_options, text_tooltip, TabOptions, h_draggable_line, horizontal_fit, icon_text_style, icons, left_click_context_menu, menu_bar, v_draggable_line, v_line, vec2, Axis, CursorIcon, Layout, LayoutInfo, Margin, PerAxis, Rect, ScrollArea, Size, Stroke, TSTransform, Theme, UINodeParams, Vec2, UI};
 
-use super::{command::{DockingCommand, TabDragSource}, DockingNodeId, DockingNodeKind, DockingState, DockingTab, DockingTree, Tabs};
+use super::{command::{DockingCommand, TabDragSource}, drop_target::{DropOverlay, DropTarget}, DockingNodeId, DockingNodeKind, DockingState, DockingTab, DockingTree, Tabs};
+
+const FLOATING_WINDOW_SIZE: Vec2 = vec2(400.0, 300.0);
+const FLOATING_WINDOW_MIN_SIZE: Vec2 = vec2(150.0, 100.0);
+
+/// Where a tab should go when it is dropped at a given position
+fn float_position(mouse_pos: Vec2) -> Vec2 {
+    mouse_pos - vec2(FLOATING_WINDOW_SIZE.x / 2.0, 10.0)
+}
 
 impl<Tab: DockingTab> Tabs<Tab> {
 
-    fn render_tab(&mut self, ui: &mut UI, node_id: DockingNodeId, tab_idx: usize, commands: &mut Vec<DockingCommand<Tab>>) {
+    /// Render a tab in the tab bar, returning its screen-space rect from the last frame
+    fn render_tab(&mut self, ui: &mut UI, node_id: DockingNodeId, tab_idx: usize, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context) -> Rect {
         let selected = self.active_tab == tab_idx;
         let theme = ui.style::<Theme>();
         let base_color = if selected { theme.bg_light } else { theme.bg_dark };
+        let docking_tab = &mut self.tabs[tab_idx];
+        let mut options = TabOptions::default()
+            .with_closable(docking_tab.closable())
+            .with_modified(docking_tab.modified());
+        if let Some(icon) = docking_tab.icon() {
+            options = options.with_icon(icon);
+        }
         let (dnd_response, tab_response) = dnd_source(ui, TabDragSource { node_id, tab_idx }, |ui| {
-            let tab_response = tab(ui, self.tabs[tab_idx].title(), selected);
-            if tab_response.close_button.mouse_released() {
+            let tab_response = tab_with_options(ui, docking_tab.title(), selected, options);
+            let close_clicked = tab_response.close_button.is_some_and(|close_button| close_button.mouse_released());
+            if close_clicked && docking_tab.confirm_close(context) {
                 commands.push(DockingCommand::CloseTab { tab: TabDragSource { node_id, tab_idx } });
             }
             ui.set_sense_mouse(tab_response.tab.node_ref, false);
@@ -20,52 +37,153 @@ impl<Tab: DockingTab> Tabs<Tab> {
         if dnd_response.mouse_released() && !dnd_response.drag_stopped() {
             self.active_tab = tab_idx;
         }
+        if dnd_response.mouse_double_clicked() {
+            commands.push(DockingCommand::ToggleMaximized { node_id });
+        }
         button_fill_animation(ui, tab_response.node_ref, &dnd_response, base_color); 
+        if let Some(tooltip) = self.tabs[tab_idx].tooltip() {
+            text_tooltip(ui, &dnd_response, tooltip);
+        }
+
+        let mut entry_clicked = false;
+        context_menu(ui, &dnd_response, |ui| {
+            entry_clicked = self.render_tab_context_menu(ui, node_id, tab_idx, commands, context);
+        });
+        if entry_clicked {
+            close_context_menu(ui, dnd_response.id);
+        }
+        ui.memory().get::<LayoutInfo>(dnd_response.id).screen_rect
+    }
+
+    /// Close the closable tabs whose indices match a predicate, from last to first so that the indices of the other tabs stay valid
+    fn close_tabs_where<P: Fn(usize) -> bool>(&mut self, node_id: DockingNodeId, predicate: P, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context) {
+        for tab_idx in (0..self.tabs.len()).rev() {
+            let tab = &mut self.tabs[tab_idx];
+            if predicate(tab_idx) && tab.closable() && tab.confirm_close(context) {
+                commands.push(DockingCommand::CloseTab { tab: TabDragSource { node_id, tab_idx } });
+            }
+        }
     }
 
-    fn render(&mut self, ui: &mut UI, node_id: DockingNodeId, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context) {
+    /// Render the built-in docking actions for a tab, followed by the tab's own context menu entries.
+    /// Returns true if an entry was clicked, so that the menu can be closed.
+    fn render_tab_context_menu(&mut self, ui: &mut UI, node_id: DockingNodeId, tab_idx: usize, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context) -> bool {
+        let tab = || TabDragSource { node_id, tab_idx };
+        let mut clicked = false;
 
+        if self.tabs[tab_idx].closable() && menu_button(ui, "Close").mouse_clicked() {
+            self.close_tabs_where(node_id, |idx| idx == tab_idx, commands, context);
+            clicked = true;
+        }
+        if menu_button(ui, "Close Others").mouse_clicked() {
+            self.close_tabs_where(node_id, |idx| idx != tab_idx, commands, context);
+            clicked = true;
+        }
+        if menu_button(ui, "Close All").mouse_clicked() {
+            self.close_tabs_where(node_id, |_| true, commands, context);
+            clicked = true;
+        }
+        h_line(ui);
+        if menu_button(ui, "Split Right").mouse_clicked() {
+            commands.push(DockingCommand::Split { tab: tab(), to: node_id, direction: Axis::X, max: true });
+            clicked = true;
+        }
+        if menu_button(ui, "Split Down").mouse_clicked() {
+            commands.push(DockingCommand::Split { tab: tab(), to: node_id, direction: Axis::Y, max: true });
+            clicked = true;
+        }
+        if menu_button(ui, "Move to New Floating Window").mouse_clicked() {
+            let position = float_position(ui.input().mouse_pos.unwrap_or(Vec2::ZERO));
+            commands.push(DockingCommand::Float { tab: tab(), position, size: FLOATING_WINDOW_SIZE });
+            clicked = true;
+        }
+
+        self.tabs[tab_idx].context_menu(ui, context) || clicked
+    }
+
+    /// Render the tab bar, letting tabs be dropped between the existing tabs
+    fn render_tab_bar(&mut self, ui: &mut UI, node_id: DockingNodeId, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context, mouse_pos: Option<Vec2>) {
         let theme = ui.style::<Theme>();
         let window_bg = theme.bg_light;
         let margin = theme.widget_margin;
-        let split_overlay_stroke_color = theme.text_active;
+        let insertion_marker_color = theme.text_active;
 
-        menu_bar(ui, |ui| {
+        // Tabs closing themselves are closed from last to first, so that the indices of the other tabs stay valid
+        for tab_idx in (0..self.tabs.len()).rev() {
+            if self.tabs[tab_idx].wants_to_close() {
+                commands.push(DockingCommand::CloseTab { tab: TabDragSource { node_id, tab_idx } });
+            }
+        }
 
-            ScrollArea::default()
-                .hide_scroll_bars()
-                .with_size(Size::fit(), Size::fr(1.0))
-                .render(ui, |ui| {
-                    horizontal_fit(ui, |ui| { 
-                        for tab_idx in 0..self.tabs.len() {
-                            self.render_tab(ui, node_id, tab_idx, commands);
-                            v_line(ui);
-                        }
-                    });
-            });
+        let mut tab_rects = Vec::new();
+        let tabs_response = ScrollArea::default()
+            .hide_scroll_bars()
+            .with_size(Size::fit(), Size::fr(1.0))
+            .render(ui, |ui| {
+                horizontal_fit(ui, |ui| { 
+                    for tab_idx in 0..self.tabs.len() {
+                        tab_rects.push(self.render_tab(ui, node_id, tab_idx, commands, context));
+                        v_line(ui);
+                    }
+                });
+        });
 
-            let (_, added_tab) = dnd_drop_zone_with_size::<TabDragSource, _>(ui, Size::fr(1.0), Size::fr(1.0), |_| {});
-            if let Some(added_tab) = added_tab {
-                commands.push(DockingCommand::MoveTab { from: added_tab, to: node_id });
+        let spacer = ui.node(UINodeParams::new(Size::fr(1.0), Size::fr(1.0)));
+
+        // Tab dropping
+        let tabs_rect = ui.memory().get::<LayoutInfo>(tabs_response.id).screen_rect;
+        let spacer_rect = ui.memory().get::<LayoutInfo>(spacer.id).screen_rect;
+        let drop_rect = Rect::min_max(tabs_rect.tl(), spacer_rect.br());
+        let drop_pos = mouse_pos.filter(|mouse_pos| drop_rect.contains(*mouse_pos) && ui.memory().has_dnd_payload_of_type::<TabDragSource>());
+        if let Some(drop_pos) = drop_pos {
+            let insert_idx = tab_rects.iter().filter(|rect| rect.center().x < drop_pos.x).count();
+            if ui.input().l_mouse.released() {
+                if let Some(tab) = ui.memory().take_dnd_payload::<TabDragSource>() {
+                    commands.push(DockingCommand::MoveTab { from: tab, to: node_id, idx: insert_idx });
+                    ui.request_redraw();
+                }
+            } else {
+                let marker_x = match tab_rects.get(insert_idx) {
+                    Some(rect) => rect.left(),
+                    None => tab_rects.last().map(|rect| rect.right()).unwrap_or(drop_rect.left())
+                };
+                let (marker, _) = ui.layer(|ui| {
+                    ui.node(
+                        UINodeParams::new(Size::px(2.0), Size::px(drop_rect.height()))
+                            .with_fill(insertion_marker_color)
+                    );
+                });
+                ui.set_transform(marker, TSTransform::translation(vec2(marker_x - 1.0, drop_rect.top())));
             }
+        }
 
-            v_line(ui);
+        v_line(ui);
 
-            let icon_text_style = icon_text_style(ui);
-            let add_tab_button = ui.node(
-                UINodeParams::new(Size::text().no_shrink(), Size::text())
-                    .with_text(icons::PLUS)
-                    .with_text_style(icon_text_style)
-                    .with_margin(Margin::same(margin))
-                    .sense_mouse()
-            );
-            button_fill_animation(ui, add_tab_button.node_ref, &add_tab_button, window_bg);
+        let icon_text_style = icon_text_style(ui);
+        let add_tab_button = ui.node(
+            UINodeParams::new(Size::text().no_shrink(), Size::text())
+                .with_text(icons::PLUS)
+                .with_text_style(icon_text_style)
+                .with_margin(Margin::same(margin))
+                .sense_mouse()
+        );
+        button_fill_animation(ui, add_tab_button.node_ref, &add_tab_button, window_bg);
 
-            left_click_context_menu(ui, &add_tab_button, |ui| {
-                Tab::add_tab_dropdown(ui, |tab| {
-                    commands.push(DockingCommand::AddTab { tab, to: node_id });
-                }, context);
-            });
+        left_click_context_menu(ui, &add_tab_button, |ui| {
+            Tab::add_tab_dropdown(ui, |tab| {
+                commands.push(DockingCommand::AddTab { tab, to: node_id });
+            }, context);
+        });
+    }
+
+    /// Render the tabs node. `mouse_pos` is None if the mouse is covered by a floating window above the node.
+    fn render(&mut self, ui: &mut UI, node_id: DockingNodeId, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context, mouse_pos: Option<Vec2>) {
+
+        let theme = ui.style::<Theme>();
+        let window_bg = theme.bg_light;
+
+        menu_bar(ui, |ui| {
+            self.render_tab_bar(ui, node_id, commands, context, mouse_pos);
         });
         
         let response = ui.node(
@@ -73,59 +191,25 @@ impl<Tab: DockingTab> Tabs<Tab> {
                 .with_fill(window_bg)
         );
 
-        // Splitting
-        let mut split_left = false;
-        let mut split_right = false;
-        let mut split_up = false;
-        let mut split_down = false;
-        if ui.memory().has_dnd_payload_of_type::<TabDragSource>() {
-            if let Some(mouse_pos) = ui.input().mouse_pos {
-                let rect = ui.memory().get::<LayoutInfo>(response.id).screen_rect;
-                if rect.contains(mouse_pos) {
-                    let delta = mouse_pos - rect.center(); 
-                    let h_split = delta.abs().max_axis() == Axis::X;
-                    split_left = h_split && delta.x < 0.0; 
-                    split_right = h_split && delta.x > 0.0;
-                    split_up = !h_split && delta.y < 0.0;
-                    split_down = !h_split && delta.y > 0.0;
-                    ui.set_on_paint(response.node_ref, move |painter, rect| {
-                        let stroke = Stroke::new(split_overlay_stroke_color, 2.0); 
-                        if split_left {
-                            painter.rect(PaintRect::new(rect.left_half(), Color::TRANSPARENT).with_stroke(stroke));
-                        }
-                        if split_right {
-                            painter.rect(PaintRect::new(rect.right_half(), Color::TRANSPARENT).with_stroke(stroke));
-                        }
-                        if split_up {
-                            painter.rect(PaintRect::new(rect.top_half(), Color::TRANSPARENT).with_stroke(stroke));
-                        }
-                        if split_down {
-                            painter.rect(PaintRect::new(rect.bottom_half(), Color::TRANSPARENT).with_stroke(stroke));
-                        }
-                    });
-                }
-            }
-        }
-        if ui.input().l_mouse.released() {
-            if split_left {
+        // Dropping tabs into the node, next to it or outside of the dock
+        self.content_rect = ui.memory().get::<LayoutInfo>(response.id).screen_rect;
+        let rect = self.content_rect;
+        let drop_pos = mouse_pos.filter(|mouse_pos| rect.contains(*mouse_pos) && ui.memory().has_dnd_payload_of_type::<TabDragSource>());
+        if let Some(drop_pos) = drop_pos {
+            let overlay = DropOverlay::new(DropTarget::ALL, |target| target.center_button_rect(rect), drop_pos);
+            if ui.input().l_mouse.released() {
                 if let Some(tab) = ui.memory().take_dnd_payload::<TabDragSource>() {
-                    commands.push(DockingCommand::Split { tab, to: node_id, direction: Axis::X, max: false });
-                }
-            }
-            if split_right {
-                if let Some(tab) = ui.memory().take_dnd_payload::<TabDragSource>() {
-                    commands.push(DockingCommand::Split { tab, to: node_id, direction: Axis::X, max: true });
-                }
-            }
-            if split_up {
-                if let Some(tab) = ui.memory().take_dnd_payload::<TabDragSource>() {
-                    commands.push(DockingCommand::Split { tab, to: node_id, direction: Axis::Y, max: false });
-                }
-            }
-            if split_down {
-                if let Some(tab) = ui.memory().take_dnd_payload::<TabDragSource>() {
-                    commands.push(DockingCommand::Split { tab, to: node_id, direction: Axis::Y, max: true });
+                    commands.push(match overlay.hovered {
+                        Some(target) => match target.split() {
+                            Some((direction, max)) => DockingCommand::Split { tab, to: node_id, direction, max },
+                            None => DockingCommand::MoveTab { from: tab, to: node_id, idx: usize::MAX }
+                        },
+                        None => DockingCommand::Float { tab, position: float_position(drop_pos), size: FLOATING_WINDOW_SIZE }
+                    });
+                    ui.request_redraw();
                 }
+            } else {
+                overlay.render(ui, rect);
             }
         }
 
@@ -142,11 +226,11 @@ impl<Tab: DockingTab> Tabs<Tab> {
 
 impl<Tab: DockingTab> DockingTree<Tab> {
 
-    fn render_node(&mut self, ui: &mut UI, node_id: DockingNodeId, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context) -> Option<()> {
+    fn render_node(&mut self, ui: &mut UI, node_id: DockingNodeId, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context, mouse_pos: Option<Vec2>) -> Option<()> {
         let node = self.nodes.get_mut(&node_id)?;
         match &mut node.kind {
             DockingNodeKind::Tabs(tabs) => {
-                tabs.render(ui, node_id, commands, context);
+                tabs.render(ui, node_id, commands, context, mouse_pos);
             },
             DockingNodeKind::Split(split) => {
                 let nodes = split.nodes.clone();
@@ -159,10 +243,26 @@ impl<Tab: DockingTab> DockingTree<Tab> {
                 let size = ui.memory().get::<LayoutInfo>(response.id).rect.size().on_axis(direction);
                 ui.with_parent(response.node_ref, |ui| {
                     for i in 0..nodes.len() {
+                        // While a node is maximized, the nodes around it are replaced by empty placeholders.
+                        // This keeps the ids of the maximized node's contents the same as when it is docked normally.
+                        if self.maximized.is_some() {
+                            if self.is_hidden(nodes[i].1) {
+                                ui.node(UINodeParams::new(Size::px(0.0), Size::px(0.0)));
+                            } else {
+                                ui.with_node(UINodeParams::new(Size::fr(1.0), Size::fr(1.0)), |ui| {
+                                    self.render_node(ui, nodes[i].1, commands, context, mouse_pos);
+                                });
+                            }
+                            if i < nodes.len() - 1 {
+                                ui.node(UINodeParams::new(Size::px(0.0), Size::px(0.0)));
+                            }
+                            continue;
+                        }
+
                         ui.with_node(
                             UINodeParams::new_per_axis(PerAxis::along_across(direction, Size::fr(nodes[i].0), Size::fr(1.0))),
                             |ui| {
-                                self.render_node(ui, nodes[i].1, commands, context);
+                                self.render_node(ui, nodes[i].1, commands, context, mouse_pos);
                             }
                         );
                         if i < nodes.len() - 1 {
@@ -199,14 +299,173 @@ impl<Tab: DockingTab> DockingTree<Tab> {
         Some(())
     }
 
-    fn render(&mut self, ui: &mut UI, context: &mut Tab::Context) {
+    /// Find the docked tabs node whose contents contain a position
+    fn docked_tabs_at(&self, node_id: DockingNodeId, pos: Vec2) -> Option<DockingNodeId> {
+        match &self.get(node_id)?.kind {
+            _ if self.is_hidden(node_id) => None,
+            DockingNodeKind::Tabs(tabs) => tabs.content_rect.contains(pos).then_some(node_id),
+            DockingNodeKind::Split(split) => split.nodes.iter().find_map(|(_, child)| self.docked_tabs_at(*child, pos))
+        }
+    }
+
+    /// The mouse position, unless it is covered by one of the floating windows from `floating_idx` upwards
+    fn uncovered_mouse_pos(&self, ui: &mut UI, floating_idx: usize) -> Option<Vec2> {
+        let mouse_pos = ui.input().mouse_pos?;
+        let covered = self.floating[floating_idx..].iter().any(|window| Rect::min_size(window.position, window.size).contains(mouse_pos));
+        (!covered).then_some(mouse_pos)
+    }
+
+    fn render_floating_window(&mut self, ui: &mut UI, floating_idx: usize, commands: &mut Vec<DockingCommand<Tab>>, context: &mut Tab::Context) {
+        let theme = ui.style::<Theme>();
+        let fill = theme.bg_dark;
+        let stroke = theme.widget_stroke();
+        let highlight_stroke_color = theme.text_active;
+        let icon_text_style = icon_text_style(ui);
+
+        // Keep the window on screen, so that it can always be grabbed
+        let window_size = ui.window_size();
+        let window = &mut self.floating[floating_idx];
+        let max_position = (window_size - window.size).max(Vec2::ZERO);
+        window.position = window.position.min(max_position).max(Vec2::ZERO);
+        let window_node = window.node;
+        let position = window.position;
+        let size = window.size;
+
+        let mouse_pos = self.uncovered_mouse_pos(ui, floating_idx + 1);
+        if ui.input().l_mouse.pressed() && mouse_pos.is_some_and(|mouse_pos| Rect::min_size(position, size).contains(mouse_pos)) {
+            commands.push(DockingCommand::RaiseFloating { window: window_node });
+        }
+
+        let (layer, (grab_bar, resize_grip)) = ui.layer(|ui| {
+            let (_, result) = ui.with_node(
+                UINodeParams::new(Size::px(size.x), Size::px(size.y))
+                    .with_layout(Layout::vertical())
+                    .with_fill(fill)
+                    .with_stroke(stroke)
+                    .sense_mouse(),
+                |ui| {
+                    let (grab_bar, _) = ui.with_node(
+                        UINodeParams::new(Size::fr(1.0), Size::fit())
+                            .with_layout(Layout::horizontal().justify_center())
+                            .sense_mouse(),
+                        |ui| {
+                            ui.node(
+                                UINodeParams::new(Size::text(), Size::text())
+                                    .with_text(icons::DOTS_SIX)
+                                    .with_text_style(icon_text_style)
+                            );
+                        }
+                    );
+
+                    ui.with_node(UINodeParams::new(Size::fr(1.0), Size::fr(1.0)), |ui| {
+                        self.render_node(ui, window_node, commands, context, mouse_pos);
+                    });
+
+                    let (_, resize_grip) = ui.with_node(
+                        UINodeParams::new(Size::fr(1.0), Size::fit())
+                            .with_layout(Layout::horizontal().justify_max()),
+                        |ui| {
+                            ui.node(
+                                UINodeParams::new(Size::text(), Size::text())
+                                    .with_text(icons::RESIZE)
+                                    .with_text_style(icon_text_style)
+                                    .sense_mouse()
+                            )
+                        }
+                    );
+
+                    (grab_bar, resize_grip)
+                }
+            );
+            result
+        });
+        ui.set_transform(layer, TSTransform::translation(position));
+
+        // Moving and docking
+        if grab_bar.hovered || grab_bar.dragging() {
+            ui.set_cursor(CursorIcon::Grab);
+        }
+        if grab_bar.drag_started() {
+            grab_bar.request_focus(ui);
+        }
+        if grab_bar.dragging() {
+            let amount = grab_bar.drag_delta(ui);
+            commands.push(DockingCommand::MoveFloating { window: window_node, amount });
+        }
+        let dock_target = ui.input().mouse_pos.and_then(|mouse_pos| self.docked_tabs_at(self.root, mouse_pos));
+        if let (true, Some(dock_target)) = (grab_bar.dragging(), dock_target) {
+            let target_rect = self.get_tabs(dock_target).map(|tabs| tabs.content_rect).unwrap_or(Rect::ZERO);
+            let (highlight, _) = ui.layer(|ui| {
+                ui.node(
+                    UINodeParams::new(Size::px(target_rect.width()), Size::px(target_rect.height()))
+                        .with_stroke(Stroke::new(highlight_stroke_color, 2.0))
+                );
+            });
+            ui.set_transform(highlight, TSTransform::translation(target_rect.tl()));
+        }
+        if grab_bar.drag_stopped() {
+            grab_bar.release_focus(ui);
+            if let Some(dock_target) = dock_target {
+                commands.push(DockingCommand::Dock { window: window_node, to: dock_target });
+            }
+        }
+
+        // Resizing
+        if resize_grip.hovered || resize_grip.dragging() {
+            ui.set_cursor(CursorIcon::NwseResize);
+        }
+        if resize_grip.drag_started() {
+            resize_grip.request_focus(ui);
+        }
+        if resize_grip.dragging() {
+            let amount = resize_grip.drag_delta(ui);
+            commands.push(DockingCommand::ResizeFloating { window: window_node, amount, min_size: FLOATING_WINDOW_MIN_SIZE });
+        }
+        if resize_grip.drag_stopped() {
+            resize_grip.release_focus(ui);
+        }
+    }
+
+    /// Render the docked nodes and the floating windows. `dock_rect` is the screen-space rect of the whole docking area.
+    fn render(&mut self, ui: &mut UI, context: &mut Tab::Context, dock_rect: Rect) {
         let mut commands = Vec::new();
 
-        self.render_node(ui, self.root, &mut commands, context);
+        // Forget the maximized node if it was removed
+        if self.maximized.is_some_and(|node_id| self.get_tabs(node_id).is_none()) {
+            self.maximized = None;
+        }
+
+        // Tabs dropped on the edges of the docking area split the root
+        let mut mouse_pos = self.uncovered_mouse_pos(ui, 0);
+        let mut root_overlay = None;
+        if let Some(drop_pos) = mouse_pos.filter(|mouse_pos| dock_rect.contains(*mouse_pos) && ui.memory().has_dnd_payload_of_type::<TabDragSource>()) {
+            let overlay = DropOverlay::new(DropTarget::EDGES, |target| target.edge_button_rect(dock_rect), drop_pos);
+            if let Some((direction, max)) = overlay.hovered.and_then(DropTarget::split) {
+                // The nodes below the edge buttons should not react to the tab
+                mouse_pos = None;
+                if ui.input().l_mouse.released() {
+                    if let Some(tab) = ui.memory().take_dnd_payload::<TabDragSource>() {
+                        commands.push(DockingCommand::Split { tab, to: self.root, direction, max });
+                        ui.request_redraw();
+                    }
+                }
+            }
+            root_overlay = Some(overlay);
+        }
+
+        self.render_node(ui, self.root, &mut commands, context, mouse_pos);
 
-        for command in commands {
-            self.execute_command(command);
+        if let Some(overlay) = root_overlay {
+            if ui.memory().has_dnd_payload_of_type::<TabDragSource>() {
+                overlay.render(ui, dock_rect);
+            }
         }
+
+        for floating_idx in 0..self.floating.len() {
+            self.render_floating_window(ui, floating_idx, &mut commands, context);
+        }
+
+        self.execute_commands(commands);
     }
 
 }
@@ -214,9 +473,20 @@ impl<Tab: DockingTab> DockingTree<Tab> {
 impl<Tab: DockingTab> DockingState<Tab> {
 
     pub fn render(&mut self, ui: &mut UI, context: &mut Tab::Context) {
-        ui.with_node(UINodeParams::new(Size::fr(1.0), Size::fr(1.0)), |ui| {
-            self.tree.render(ui, context);
+        let response = ui.node(UINodeParams::new(Size::fr(1.0), Size::fr(1.0)));
+        let dock_rect = ui.memory().get::<LayoutInfo>(response.id).screen_rect;
+        ui.with_parent(response.node_ref, |ui| {
+            self.tree.render(ui, context, dock_rect);
         });
+
+        // Tabs dropped outside of the docking area become floating windows
+        if ui.input().l_mouse.released() && ui.memory().has_dnd_payload_of_type::<TabDragSource>() {
+            if let Some(mouse_pos) = ui.input().mouse_pos.filter(|mouse_pos| !dock_rect.contains(*mouse_pos)) {
+                if let Some(tab) = ui.memory().take_dnd_payload::<TabDragSource>() {
+                    self.tree.execute_command(DockingCommand::Float { tab, position: float_position(mouse_pos), size: FLOATING_WINDOW_SIZE });
+                }
+            }
+        }
     }
 
 }
@@ -0,0 +1,353 @@
+
+use crate::{vec2, Axis, Headless, Role, UI};
+
+use super::{command::{DockingCommand, TabDragSource}, DockingNodeId, DockingNodeKind, DockingState, DockingTab, DockingTabRef, DockingTree};
+
+struct TestTab(&'static str);
+
+impl DockingTab for TestTab {
+
+    type Context = ();
+
+    fn title(&self) -> String {
+        self.0.to_owned()
+    }
+
+    fn render(&mut self, ui: &mut UI, _context: &mut ()) {
+        crate::label(ui, self.0);
+    }
+
+    fn add_tab_dropdown<F: FnMut(Self)>(_ui: &mut UI, _add_tab: F, _context: &mut ()) {
+
+    }
+
+}
+
+fn state(titles: &[&'static str]) -> DockingState<TestTab> {
+    DockingState::new(titles.iter().map(|title| TestTab(title)).collect())
+}
+
+/// The titles of all the tabs, docked first, then floating
+fn titles(state: &DockingState<TestTab>) -> Vec<&'static str> {
+    state.tabs().map(|(_, tab)| tab.0).collect()
+}
+
+fn node_titles(state: &DockingState<TestTab>, node: DockingNodeId) -> Vec<&'static str> {
+    state.tree.get_tabs(node).map(|tabs| tabs.tabs.iter().map(|tab| tab.0).collect()).unwrap_or_default()
+}
+
+fn find(state: &DockingState<TestTab>, title: &str) -> DockingTabRef {
+    state.find_tab(|tab| tab.0 == title).unwrap()
+}
+
+fn split_direction(tree: &DockingTree<TestTab>, node: DockingNodeId) -> Option<Axis> {
+    tree.get_split(node).map(|split| split.direction)
+}
+
+/// Check that every node can be reached from the root or a floating window, that parent links match,
+/// and that there are no empty tabs nodes or splits with less than two children
+fn check_tree(tree: &DockingTree<TestTab>) {
+    fn check_node(tree: &DockingTree<TestTab>, id: DockingNodeId, parent: DockingNodeId, n_nodes: &mut usize) {
+        let node = tree.get(id).expect("missing node");
+        assert_eq!(node.parent, parent, "wrong parent");
+        *n_nodes += 1;
+        match &node.kind {
+            DockingNodeKind::Tabs(tabs) => assert!(!tabs.tabs.is_empty() || id == tree.root, "empty tabs node"),
+            DockingNodeKind::Split(split) => {
+                assert!(split.nodes.len() >= 2, "split with less than two children");
+                for (_, child) in &split.nodes {
+                    check_node(tree, *child, id, n_nodes);
+                }
+            }
+        }
+    }
+
+    let mut n_nodes = 0;
+    check_node(tree, tree.root, DockingNodeId::NULL, &mut n_nodes);
+    for window in &tree.floating {
+        check_node(tree, window.node, DockingNodeId::NULL, &mut n_nodes);
+    }
+    assert_eq!(n_nodes, tree.nodes.len(), "unreachable nodes");
+}
+
+fn float(state: &mut DockingState<TestTab>, title: &str) -> DockingNodeId {
+    let tab = find(state, title);
+    state.tree.execute_command(DockingCommand::Float { tab: tab.into(), position: vec2(10.0, 10.0), size: vec2(200.0, 100.0) });
+    state.tree.floating.last().unwrap().node
+}
+
+#[test]
+fn add_tab_goes_to_first_tabs_of_split() {
+    let mut state = state(&["a"]);
+    let root = state.root();
+    let b = state.add_tab_split(TestTab("b"), root, Axis::X, true).unwrap();
+    assert_eq!(split_direction(&state.tree, state.root()), Some(Axis::X));
+
+    let c = state.add_tab(TestTab("c"), state.root()).unwrap();
+    assert_eq!(c.node, root);
+    assert_eq!(node_titles(&state, root), ["a", "c"]);
+    assert_eq!(state.tree.get_tabs(root).unwrap().active_tab, 1);
+    assert_eq!(node_titles(&state, b.node), ["b"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn add_tab_to_stale_node() {
+    let mut state = state(&["a"]);
+    assert!(state.add_tab(TestTab("b"), DockingNodeId(1000)).is_none());
+    assert!(state.add_tab_split(TestTab("b"), DockingNodeId(1000), Axis::X, true).is_none());
+    assert_eq!(titles(&state), ["a"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn moving_last_tab_collapses_split() {
+    let mut state = state(&["a"]);
+    let root = state.root();
+    let b = state.add_tab_split(TestTab("b"), root, Axis::X, true).unwrap();
+    state.move_tab(b, root);
+    assert_eq!(state.root(), root);
+    assert_eq!(node_titles(&state, root), ["a", "b"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn move_tab_to_stale_node_keeps_tab() {
+    let mut state = state(&["a", "b"]);
+    state.move_tab(find(&state, "b"), DockingNodeId(1000));
+    assert_eq!(titles(&state), ["a", "b"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn split_next_to_collapsing_split() {
+    // X[A(a, b), Y[C(c), D(d)]]
+    let mut state = state(&["a", "b"]);
+    let root = state.root();
+    let c = state.add_tab_split(TestTab("c"), root, Axis::X, true).unwrap();
+    state.add_tab_split(TestTab("d"), c.node, Axis::Y, true).unwrap();
+    let y = state.tree.get_parent(c.node).unwrap();
+    assert_eq!(split_direction(&state.tree, y), Some(Axis::Y));
+
+    // Taking c out of its node collapses the split it is being put next to
+    let new_c = state.split(c, y, Axis::X, true).unwrap();
+    assert_eq!(state.get_tab(new_c).unwrap().0, "c");
+    let mut all_titles = titles(&state);
+    all_titles.sort();
+    assert_eq!(all_titles, ["a", "b", "c", "d"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn split_next_to_root_split() {
+    let mut state = state(&["a", "b"]);
+    let root = state.root();
+    state.add_tab_split(TestTab("c"), root, Axis::X, true).unwrap();
+    let x = state.root();
+
+    let b = state.split(find(&state, "b"), x, Axis::Y, true).unwrap();
+    let new_root = state.root();
+    assert_eq!(split_direction(&state.tree, new_root), Some(Axis::Y));
+    assert_eq!(state.tree.get_parent(x), Some(new_root));
+    assert_eq!(state.tree.get_parent(b.node), Some(new_root));
+    check_tree(&state.tree);
+}
+
+#[test]
+fn split_when_taking_tab_collapses_root() {
+    let mut state = state(&["a"]);
+    let root = state.root();
+    let b = state.add_tab_split(TestTab("b"), root, Axis::X, true).unwrap();
+
+    // Taking b collapses the root split, so b ends up next to a
+    let b = state.split(b, state.root(), Axis::Y, true).unwrap();
+    assert_eq!(split_direction(&state.tree, state.root()), Some(Axis::Y));
+    assert_eq!(state.get_tab(b).unwrap().0, "b");
+    assert_eq!(node_titles(&state, root), ["a"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn split_with_stale_refs_keeps_tab() {
+    let mut state = state(&["a", "b"]);
+    let root = state.root();
+    assert!(state.split(find(&state, "b"), DockingNodeId(1000), Axis::X, true).is_none());
+    assert!(state.split(DockingTabRef { node: root, tab_idx: 5 }, root, Axis::X, true).is_none());
+    assert!(state.split(DockingTabRef { node: DockingNodeId(1000), tab_idx: 0 }, root, Axis::X, true).is_none());
+    assert_eq!(node_titles(&state, root), ["a", "b"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn split_only_tab_next_to_itself() {
+    let mut state = state(&["a"]);
+    let root = state.root();
+    let b = state.add_tab_split(TestTab("b"), root, Axis::X, true).unwrap();
+    assert_eq!(state.split(b, b.node, Axis::Y, true), Some(b));
+    assert_eq!(node_titles(&state, b.node), ["b"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn split_tab_out_of_floating_window() {
+    let mut state = state(&["a", "b", "c"]);
+    let window = float(&mut state, "b");
+    state.add_tab(TestTab("d"), window).unwrap();
+
+    let b = state.split(find(&state, "b"), window, Axis::X, false).unwrap();
+    let window_root = state.tree.floating[0].node;
+    assert_eq!(split_direction(&state.tree, window_root), Some(Axis::X));
+    assert_eq!(state.tree.get_root(b.node), Some(window_root));
+    assert_eq!(node_titles(&state, b.node), ["b"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn close_tab() {
+    let mut state = state(&["a", "b"]);
+    let root = state.root();
+    assert_eq!(state.close_tab(find(&state, "a")).unwrap().0, "a");
+    assert_eq!(node_titles(&state, root), ["b"]);
+
+    // The last tab of the root node stays open
+    assert!(state.close_tab(find(&state, "b")).is_none());
+    assert!(state.close_tab(DockingTabRef { node: root, tab_idx: 3 }).is_none());
+    assert_eq!(titles(&state), ["b"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn closing_last_floating_tab_closes_window() {
+    let mut state = state(&["a", "b"]);
+    float(&mut state, "b");
+    assert_eq!(state.tree.floating.len(), 1);
+    state.close_tab(find(&state, "b")).unwrap();
+    assert!(state.tree.floating.is_empty());
+    assert_eq!(titles(&state), ["a"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn close_tabs_in_nested_splits_and_floating_windows() {
+    let mut state = state(&["a", "x1"]);
+    let root = state.root();
+    let b = state.add_tab_split(TestTab("x2"), root, Axis::X, true).unwrap();
+    state.add_tab_split(TestTab("c"), b.node, Axis::Y, true).unwrap();
+    state.add_tab(TestTab("x3"), b.node).unwrap();
+    state.add_tab(TestTab("x4"), root).unwrap();
+    float(&mut state, "x4");
+
+    state.close_tabs(|tab| tab.0.starts_with('x'));
+    assert_eq!(titles(&state), ["a", "c"]);
+    assert!(state.tree.floating.is_empty());
+    check_tree(&state.tree);
+}
+
+#[test]
+fn closing_several_tabs_in_one_frame() {
+    let mut state = state(&["a", "b", "c", "d"]);
+    let root = state.root();
+    let close = |tab_idx| DockingCommand::CloseTab { tab: TabDragSource { node_id: root, tab_idx } };
+    state.tree.execute_commands(vec![close(0), close(2), close(0)]);
+    assert_eq!(node_titles(&state, root), ["b", "d"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn closing_and_moving_tabs_in_one_frame() {
+    let mut state = state(&["a", "b", "c", "d"]);
+    let root = state.root();
+    let other = state.add_tab_split(TestTab("e"), root, Axis::X, true).unwrap().node;
+
+    // The close was queued first, so it applies to the tab it was queued for and the move goes stale
+    state.tree.execute_commands(vec![
+        DockingCommand::CloseTab { tab: TabDragSource { node_id: root, tab_idx: 0 } },
+        DockingCommand::MoveTab { from: TabDragSource { node_id: root, tab_idx: 3 }, to: other, idx: 0 }
+    ]);
+    assert_eq!(node_titles(&state, root), ["b", "c", "d"]);
+    assert_eq!(node_titles(&state, other), ["e"]);
+
+    // The move was queued first, so the close applies to the tabs after the move
+    state.tree.execute_commands(vec![
+        DockingCommand::MoveTab { from: TabDragSource { node_id: root, tab_idx: 2 }, to: other, idx: 0 },
+        DockingCommand::CloseTab { tab: TabDragSource { node_id: root, tab_idx: 0 } },
+        DockingCommand::CloseTab { tab: TabDragSource { node_id: other, tab_idx: 1 } }
+    ]);
+    assert_eq!(node_titles(&state, root), ["c"]);
+    assert_eq!(node_titles(&state, other), ["d"]);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn focus_tab() {
+    let mut state = state(&["a", "b", "c"]);
+    let root = state.root();
+    assert!(state.focus_tab(find(&state, "b")));
+    assert_eq!(state.tree.get_tabs(root).unwrap().active_tab, 1);
+    assert!(!state.focus_tab(DockingTabRef { node: root, tab_idx: 3 }));
+    assert!(!state.focus_tab(DockingTabRef { node: DockingNodeId(1000), tab_idx: 0 }));
+
+    // Focusing a tab in a floating window brings the window to the top
+    let window_c = float(&mut state, "c");
+    let window_b = float(&mut state, "b");
+    assert_eq!(state.tree.floating.last().unwrap().node, window_b);
+    assert!(state.focus_tab(find(&state, "c")));
+    assert_eq!(state.tree.floating.last().unwrap().node, window_c);
+    check_tree(&state.tree);
+}
+
+#[test]
+fn render_tabs() {
+    let mut state = state(&["a", "b"]);
+    let root = state.root();
+    state.add_tab_split(TestTab("c"), root, Axis::X, true).unwrap();
+    float(&mut state, "b");
+
+    let mut headless = Headless::new(vec2(800.0, 600.0));
+    headless.tick(|ui| state.render(ui, &mut ()));
+    headless.tick(|ui| state.render(ui, &mut ()));
+    for title in ["a", "b", "c"] {
+        assert!(headless.query().find_by_role_and_text(Role::Tab, title).is_some(), "missing tab {}", title);
+    }
+}
+
+#[test]
+fn clicking_a_context_menu_entry_closes_the_menu() {
+    let mut state = state(&["a", "b", "c"]);
+    let mut headless = Headless::new(vec2(800.0, 600.0));
+    headless.tick(|ui| state.render(ui, &mut ()));
+    headless.tick(|ui| state.render(ui, &mut ()));
+
+    // Aim at the title, since the middle of the tab is covered by its close button
+    let title = headless.query().find_by_role_and_text(Role::Label, "b").unwrap().id;
+    headless.move_mouse_to_node(title);
+    headless.press_r_mouse();
+    headless.tick(|ui| state.render(ui, &mut ()));
+    headless.release_r_mouse();
+    headless.tick(|ui| state.render(ui, &mut ()));
+    headless.tick(|ui| state.render(ui, &mut ()));
+
+    let entry = headless.query().find_by_role_and_text(Role::MenuItem, "Close Others").expect("context menu did not open").id;
+
+    // Clicking the separator between the entries keeps the menu open
+    let close_all = headless.query().find_by_role_and_text(Role::MenuItem, "Close All").unwrap().rect;
+    let split_right = headless.query().find_by_role_and_text(Role::MenuItem, "Split Right").unwrap().rect;
+    headless.move_mouse(vec2(close_all.center().x, (close_all.bottom() + split_right.top()) / 2.0));
+    headless.press_l_mouse();
+    headless.tick(|ui| state.render(ui, &mut ()));
+    headless.release_l_mouse();
+    headless.tick(|ui| state.render(ui, &mut ()));
+    headless.tick(|ui| state.render(ui, &mut ()));
+    assert!(headless.query().get(entry).is_some(), "clicking the separator closed the context menu");
+    assert_eq!(titles(&state), ["a", "b", "c"]);
+
+    headless.move_mouse_to_node(entry);
+    headless.press_l_mouse();
+    headless.tick(|ui| state.render(ui, &mut ()));
+    headless.release_l_mouse();
+    headless.tick(|ui| state.render(ui, &mut ()));
+    headless.tick(|ui| state.render(ui, &mut ()));
+
+    assert_eq!(titles(&state), ["b"]);
+    assert!(headless.query().find_by_role_and_text(Role::MenuItem, "Close Others").is_none(), "context menu stayed open");
+}
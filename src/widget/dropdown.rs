@@ -1,7 +1,7 @@
 
-use crate::{icons, Layout, LayoutInfo, Margin, PerAxis, Size, UINodeParams, UI};
+use crate::{icons, Layout, LayoutInfo, Margin, PerAxis, Role, Size, UINodeParams, UI};
 
-use super::{button_fill_animation, close_context_menu, h_spacing, horizontal_fit_centered, icon_text_style, is_context_menu_open, label, label_text_style, open_context_menu, render_context_menu, Theme};
+use super::{button_fill_animation, close_context_menu, container, icon_text_style, is_context_menu_open, label, label_text_style, open_context_menu, render_context_menu, Theme};
 
 pub fn dropdown<S: Into<String>, F: FnOnce(&mut UI)>(ui: &mut UI, dropdown_text: S, contents: F) {
     let theme = ui.style::<Theme>(); 
@@ -16,6 +16,7 @@ pub fn dropdown<S: Into<String>, F: FnOnce(&mut UI)>(ui: &mut UI, dropdown_text:
             .with_layout(Layout::horizontal())
             .with_margin(Margin::same(widget_margin))
             .with_rounding(rounding)
+            .with_role(Role::Dropdown)
             .sense_mouse(),
         |ui| {
             ui.node(
@@ -45,9 +46,8 @@ pub fn dropdown<S: Into<String>, F: FnOnce(&mut UI)>(ui: &mut UI, dropdown_text:
 }
 
 pub fn dropdown_labeled<L: Into<String>, S: Into<String>, F: FnOnce(&mut UI)>(ui: &mut UI, label_text: L, dropdown_text: S, contents: F) {
-    horizontal_fit_centered(ui, |ui| {
+    container(ui, Size::fit(), Size::fit(), Layout::horizontal().align_center().with_main_gap(5.0), |ui| {
         label(ui, label_text);
-        h_spacing(ui, 5.0);
         dropdown(ui, dropdown_text, contents);
     });
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 
-use crate::{Response, Size, UINodeParams, UI};
+use crate::{Response, Role, Size, UINodeParams, UI};
 
 use super::label_text_style;
 
@@ -10,6 +10,7 @@ pub fn label<S: Into<String>>(ui: &mut UI, label: S) -> Response {
         UINodeParams::new(Size::text(), Size::text())
             .with_text(label)
             .with_text_style(text_style)
+            .with_role(Role::Label)
     )
 
 }
@@ -1,5 +1,5 @@
 
-use crate::{Layout, Margin, Response, Size, UINodeParams, UI};
+use crate::{Anchor, GridCell, Layout, Margin, Response, Size, UINodeParams, UI};
 
 use super::Theme;
 
@@ -31,6 +31,35 @@ pub fn horizontal_fit_centered<R, F: FnOnce(&mut UI) -> R>(ui: &mut UI, body: F)
     (container, ui.with_parent(container.node_ref, body))
 }
 
+/// A horizontal layout where children that don't fit flow onto new rows, e.g. for a list of tags
+pub fn horizontal_wrap<R, F: FnOnce(&mut UI) -> R>(ui: &mut UI, gap: f32, body: F) -> (Response, R) {
+    let container = ui.node(
+        UINodeParams::new(Size::fr(1.0), Size::fit())
+            .with_layout(Layout::horizontal().with_wrap().with_gap(gap, gap))
+    );
+    (container, ui.with_parent(container.node_ref, body))
+}
+
+/// A grid with a column for each size in `columns`, e.g. for lining up the labels and values of a property inspector
+pub fn grid<R, F: FnOnce(&mut UI) -> R>(ui: &mut UI, columns: Vec<Size>, gap: f32, body: F) -> (Response, R) {
+    container(ui, Size::fr(1.0), Size::fit(), Layout::grid(columns).with_gap(gap, gap), body)
+}
+
+/// Put the contents in a particular cell of the surrounding grid, e.g. to span several columns
+pub fn grid_cell<R, F: FnOnce(&mut UI) -> R>(ui: &mut UI, cell: GridCell, body: F) -> (Response, R) {
+    ui.with_node(UINodeParams::new(Size::fr(1.0), Size::fit()).with_grid_cell(cell), body)
+}
+
+/// Children placed on top of each other, e.g. for drawing a selection outline over a thumbnail
+pub fn stack<R, F: FnOnce(&mut UI) -> R>(ui: &mut UI, body: F) -> (Response, R) {
+    container(ui, Size::fit(), Size::fit(), Layout::stack(), body)
+}
+
+/// Put the contents at a point of the surrounding node, outside of its layout, e.g. for a badge in the corner of a button
+pub fn anchored<R, F: FnOnce(&mut UI) -> R>(ui: &mut UI, anchor: Anchor, body: F) -> (Response, R) {
+    ui.with_node(UINodeParams::new(Size::fit(), Size::fit()).with_anchor(anchor), body)
+}
+
 pub fn vertical<R, F: FnOnce(&mut UI) -> R>(ui: &mut UI, body: F) -> (Response, R) {
     let container = ui.node(
         UINodeParams::new(Size::fr(1.0), Size::fit())
@@ -1,7 +1,7 @@
 
-use crate::{Axis, Margin, PerAxis, Response, Size, UINodeParams, UI};
+use crate::{Axis, Layout, Margin, PerAxis, Response, Size, UINodeParams, UI};
 
-use super::{h_spacing, horizontal, v_spacing, vertical, Theme};
+use super::Theme;
 
 fn line_params(ui: &mut UI, axis: Axis) -> UINodeParams {
     let theme = ui.style::<Theme>(); 
@@ -24,21 +24,23 @@ pub fn v_line(ui: &mut UI) {
 pub fn h_divider(ui: &mut UI) {
     let theme = ui.style::<Theme>();
     let margin = theme.widget_margin;
-    horizontal(ui, |ui| {
-        h_spacing(ui, margin);
-        h_line(ui);
-        h_spacing(ui, margin);
-    });
+    ui.with_node(
+        UINodeParams::new(Size::fr(1.0), Size::fit())
+            .with_layout(Layout::horizontal())
+            .with_margin(Margin::horizontal(margin)),
+        h_line
+    );
 }
 
 pub fn v_divider(ui: &mut UI) {
     let theme = ui.style::<Theme>();
     let margin = theme.widget_margin;
-    vertical(ui, |ui| {
-        v_spacing(ui, margin);
-        h_line(ui);
-        v_spacing(ui, margin);
-    });
+    ui.with_node(
+        UINodeParams::new(Size::fr(1.0), Size::fit())
+            .with_layout(Layout::vertical())
+            .with_margin(Margin::vertical(margin)),
+        h_line
+    );
 }
 
 const INTERACTION_MARGIN: Margin = Margin::same(5.0);
@@ -1,5 +1,5 @@
 
-use crate::{icons, vec2, Color, Id, Layout, LayoutInfo, Margin, PerAxis, Response, Size, UINodeParams, UI};
+use crate::{icons, vec2, Color, Id, Layout, LayoutInfo, Margin, PerAxis, Response, Role, Size, UINodeParams, UI};
 
 use super::{close_context_menu, h_line, horizontal, icon, is_context_menu_open, label, label_text_style, open_context_menu, render_context_menu, Theme};
 
@@ -34,6 +34,7 @@ pub fn menu_bar_item<S: Into<String>, F: FnOnce(&mut UI)>(ui: &mut UI, label: S,
             .with_rounding(rounding)
             .with_text_style(text_style)
             .with_text(label)
+            .with_role(Role::MenuItem)
             .sense_mouse()
     );
 
@@ -72,6 +73,7 @@ fn menu_button_common<S: Into<String>, F: FnOnce(&mut UI)>(ui: &mut UI, label_te
             .with_layout(Layout::horizontal())
             .with_margin(Margin::same(margin))
             .with_rounding(rounding)
+            .with_role(Role::MenuItem)
             .sense_mouse(),
         |ui| {
             label(ui, label_text);
@@ -1,7 +1,7 @@
 
-use crate::{Axis, Id, Layout, LayoutInfo, Response, Size, TSTransform, UINodeParams, Vec2, UI};
+use crate::{Axis, Id, Layout, LayoutInfo, Margin, Response, Size, TSTransform, UINodeParams, Vec2, UI};
 
-use super::{button_fill_animation, v_spacing, Theme};
+use super::{button_fill_animation, Theme};
 
 #[derive(Default)]
 struct ScrollAreaMemory {
@@ -12,7 +12,8 @@ struct ScrollAreaMemory {
 pub struct ScrollArea {
     width: Size,
     height: Size,
-    show_scroll_bars: bool
+    show_scroll_bars: bool,
+    scroll_horizontally: bool
 }
 
 impl Default for ScrollArea {
@@ -21,7 +22,8 @@ impl Default for ScrollArea {
         Self {
             width: Size::fr(1.0),
             height: Size::fr(1.0),
-            show_scroll_bars: true
+            show_scroll_bars: true,
+            scroll_horizontally: true
         }
     }
 
@@ -65,6 +67,13 @@ impl ScrollArea {
         self
     }
 
+    /// Only scroll vertically, fitting the contents to the width of the scroll area.
+    /// Useful for wrapping layouts, which need a width to wrap within.
+    pub fn no_horizontal_scroll(mut self) -> Self {
+        self.scroll_horizontally = false;
+        self
+    }
+
     pub fn render<F: FnOnce(&mut UI)>(self, ui: &mut UI, contents: F) -> Response {
 
         let scroll_area = ui.node(
@@ -87,9 +96,14 @@ impl ScrollArea {
 
             let (scroll, max_scroll, show_h_scroll_bar, show_v_scroll_bar, h_scroll_bar) = ui.with_parent(inner.node_ref, |ui| {
 
+                let (content_width, content_layout) = if self.scroll_horizontally {
+                    (Size::fit(), Layout::vertical().with_vertical_overflow().with_horizontal_overflow())
+                } else {
+                    (Size::fr(1.0), Layout::vertical().with_vertical_overflow())
+                };
                 let content_response = ui.node(
-                    UINodeParams::new(Size::fit(), Size::fr(1.0))
-                        .with_layout(Layout::vertical().with_vertical_overflow().with_horizontal_overflow())
+                    UINodeParams::new(content_width, Size::fr(1.0))
+                        .with_layout(content_layout)
                 );
                 ui.with_parent(content_response.node_ref, contents);
 
@@ -129,7 +143,12 @@ impl ScrollArea {
             });
 
             let v_scroll_bar = if show_v_scroll_bar {
-                let scroll_bar = ui.node(UINodeParams::new(Size::px(scroll_bar_size), Size::fr(1.0)));
+                // Leave the corner below the vertical scroll bar to the horizontal one
+                let corner = if show_h_scroll_bar { scroll_bar_size } else { 0.0 };
+                let scroll_bar = ui.node(
+                    UINodeParams::new(Size::px(scroll_bar_size), Size::fr(1.0))
+                        .with_margin(Margin::new(0.0, 0.0, 0.0, corner))
+                );
                 Some((scroll_bar, ui.with_parent(scroll_bar.node_ref, |ui| {
                     ui.node(UINodeParams::new(Size::fr(1.0), Size::fr(scroll.y)));
                     let v_scroll_thumb = ui.node(
@@ -138,9 +157,6 @@ impl ScrollArea {
                             .with_fill(scroll_thumb_color)
                     );
                     ui.node(UINodeParams::new(Size::fr(1.0), Size::fr(max_scroll.y - scroll.y)));
-                    if show_h_scroll_bar {
-                        v_spacing(ui, scroll_bar_size);
-                    }
                     v_scroll_thumb
                 })))
             } else { 
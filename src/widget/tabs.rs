@@ -1,14 +1,61 @@
-use crate::{icons, Layout, Margin, Response, Size, UINodeParams, UI};
+use crate::{icons, Layout, Margin, Response, Role, Size, UINodeParams, UI};
 
-use super::{button_fill_animation, h_spacing, icon_text_style, label, Theme};
+use super::{button_fill_animation, icon_text_style, label, Theme};
 
 pub struct TabResponse {
     pub tab: Response,
-    pub close_button: Response
+    /// The close button, if the tab can be closed
+    pub close_button: Option<Response>
+}
+
+/// The optional parts of a tab
+pub struct TabOptions {
+    icon: Option<&'static str>,
+    closable: bool,
+    modified: bool
+}
+
+impl Default for TabOptions {
+
+    fn default() -> Self {
+        Self {
+            icon: None,
+            closable: true,
+            modified: false
+        }
+    }
+
+}
+
+impl TabOptions {
+
+    /// Show an icon from `pierro::icons` before the tab's label
+    pub fn with_icon(mut self, icon: &'static str) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+
+    /// Mark the tab as having unsaved changes.
+    /// The close button is replaced by a dot until it is hovered.
+    pub fn with_modified(mut self, modified: bool) -> Self {
+        self.modified = modified;
+        self
+    }
+
 }
 
 /// A selectable tab with a close button. Should be used inside `pierro::menu_bar`
 pub fn tab<S: Into<String>>(ui: &mut UI, label_text: S, selected: bool) -> TabResponse {
+    tab_with_options(ui, label_text, selected, TabOptions::default())
+}
+
+/// A selectable tab with an optional icon, close button and modified marker. Should be used inside `pierro::menu_bar`
+pub fn tab_with_options<S: Into<String>>(ui: &mut UI, label_text: S, selected: bool, options: TabOptions) -> TabResponse {
     let theme = ui.style::<Theme>();
     let tab_bg = if selected { theme.bg_light } else { theme.bg_dark };
     let widget_margin = theme.widget_margin;
@@ -16,21 +63,40 @@ pub fn tab<S: Into<String>>(ui: &mut UI, label_text: S, selected: bool) -> TabRe
 
     let (tab, close_button) = ui.with_node(
         UINodeParams::new(Size::fit(), Size::fit())
-            .with_layout(Layout::horizontal())
+            .with_layout(Layout::horizontal().with_main_gap(4.0))
             .with_margin(Margin::same(widget_margin))
             .with_fill(tab_bg)
+            .with_role(Role::Tab)
             .sense_mouse(),
         |ui| {
+            if let Some(icon) = options.icon {
+                ui.node(
+                    UINodeParams::new(Size::text(), Size::text())
+                        .with_text(icon)
+                        .with_text_style(icon_style)
+                );
+            }
+
             label(ui, label_text);
-            h_spacing(ui, 6.0);
-
-            let close_button = ui.node(
-                UINodeParams::new(Size::text(), Size::text())
-                    .with_text(icons::X)
-                    .sense_mouse()
-                    .with_text_style(icon_style)
-            );
-            close_button
+
+            if !options.closable && !options.modified {
+                return None;
+            }
+
+            // The close button sits a little further from the label than the icon
+            let mut params = UINodeParams::new(Size::text(), Size::text())
+                .with_text(icons::X)
+                .with_text_style(icon_style)
+                .with_margin(Margin::new(2.0, 0.0, 0.0, 0.0));
+            if options.closable {
+                params = params.sense_mouse();
+            }
+            let close_button = ui.node(params);
+            if options.modified && !(options.closable && close_button.hovered) {
+                ui.set_text(close_button.node_ref, icons::DOT_OUTLINE);
+            }
+
+            options.closable.then_some(close_button)
         }
     );
 
@@ -1,7 +1,7 @@
 
 use cosmic_text::{Edit, FontSystem};
 
-use crate::{vec2, CursorIcon, Key, LayoutInfo, LogicalKey, PaintRect, PaintText, Rect, Size, UINodeParams, Vec2, UI};
+use crate::{vec2, CursorIcon, Key, KeyChord, LayoutInfo, LogicalKey, PaintRect, PaintText, Rect, Role, Shortcut, Size, UINodeParams, Vec2, UI};
 
 use super::{label_text_style, Theme};
 
@@ -10,6 +10,10 @@ struct TextEditMemory {
     scroll: f32
 }
 
+pub const TEXT_EDIT_COPY: Shortcut = Shortcut::new("text_edit.copy", "Copy", KeyChord::text('c').with_command()).with_context("text_edit");
+pub const TEXT_EDIT_CUT: Shortcut = Shortcut::new("text_edit.cut", "Cut", KeyChord::text('x').with_command()).with_context("text_edit");
+pub const TEXT_EDIT_PASTE: Shortcut = Shortcut::new("text_edit.paste", "Paste", KeyChord::text('v').with_command()).with_context("text_edit");
+
 fn font_system<'a>(ui: &'a mut UI) -> &'a mut FontSystem {
     ui.font_system(ui.text_font()).unwrap()
 }
@@ -31,6 +35,7 @@ pub fn text_edit(ui: &mut UI, text: &mut String) {
             .sense_mouse()
             .with_fill(color)
             .with_rounding(widget_rounding)
+            .with_role(Role::TextField)
     );
 
     if text_edit.mouse_pressed() && !text_edit.is_focused(ui) {
@@ -67,22 +72,28 @@ pub fn text_edit(ui: &mut UI, text: &mut String) {
         // Keyboard input
         ui.request_ime(text_edit.node_ref);
 
+        let paste = ui.scoped_shortcut_pressed(&TEXT_EDIT_PASTE, text_edit.id);
+        let copy = ui.scoped_shortcut_pressed(&TEXT_EDIT_COPY, text_edit.id);
+        let cut = ui.scoped_shortcut_pressed(&TEXT_EDIT_CUT, text_edit.id);
+        if paste {
+            for char in ui.get_clipboard_text().unwrap_or(String::new()).chars() {
+                memory.editor.action(font_system(ui), cosmic_text::Action::Insert(char));
+            }
+        }
+        if copy || cut {
+            if let Some(text) = memory.editor.copy_selection() {
+                ui.set_clipboard_text(text);
+            }
+        }
+        if cut {
+            memory.editor.delete_selection();
+        }
+
+        // Keys pressed while holding Ctrl/Cmd are shortcuts rather than text, unless Alt is also held for AltGr
+        let shortcut_held = ui.input().key_down(Key::COMMAND) && !ui.input().key_down(Key::ALT);
         for key in ui.input().keys_pressed.clone() {
             if let Some(text) = key.text {
-                if ui.input().key_down(Key::COMMAND) && text.to_lowercase() == "v" {
-                    for char in ui.get_clipboard_text().unwrap_or(String::new()).chars() {
-                        memory.editor.action(font_system(ui), cosmic_text::Action::Insert(char));
-                    }
-                } else if ui.input().key_down(Key::COMMAND) && text.to_lowercase() == "c" {
-                    if let Some(text) = memory.editor.copy_selection() {
-                        ui.set_clipboard_text(text);
-                    }
-                } else if ui.input().key_down(Key::COMMAND) && text.to_lowercase() == "x" {
-                    if let Some(text) = memory.editor.copy_selection() {
-                        ui.set_clipboard_text(text);
-                    }
-                    memory.editor.delete_selection();
-                } else {
+                if !(paste || copy || cut || shortcut_held) {
                     for char in text.chars() {
                         memory.editor.action(font_system(ui), cosmic_text::Action::Insert(char));
                     }
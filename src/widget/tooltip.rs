@@ -0,0 +1,60 @@
+
+use crate::{vec2, Layout, LayoutInfo, Margin, Response, Size, TSTransform, UINodeParams, Vec2, UI};
+
+use super::{label, Theme};
+
+/// How long a node must be hovered before its tooltip appears, in seconds
+const TOOLTIP_DELAY: f32 = 0.5;
+
+#[derive(Default)]
+struct TooltipMemory {
+    hover_time: f32
+}
+
+/// Show a popup next to the mouse once a node has been hovered for a moment.
+/// The node must sense the mouse.
+pub fn tooltip<F: FnOnce(&mut UI)>(ui: &mut UI, response: &Response, body: F) {
+    if !response.hovered || ui.input().l_mouse.down() {
+        ui.memory().remove::<TooltipMemory>(response.id);
+        return;
+    }
+
+    let delta_time = ui.input().delta_time;
+    let memory = ui.memory().get::<TooltipMemory>(response.id);
+    memory.hover_time += delta_time;
+    if memory.hover_time < TOOLTIP_DELAY {
+        ui.request_redraw();
+        return;
+    }
+
+    let Some(mouse_pos) = ui.input().mouse_pos else { return; };
+    let theme = ui.style::<Theme>();
+    let fill = theme.bg_popup;
+    let stroke = theme.widget_stroke();
+    let margin = theme.widget_margin;
+
+    let (layer, tooltip) = ui.layer(|ui| {
+        let (tooltip, _) = ui.with_node(
+            UINodeParams::new(Size::fit(), Size::fit())
+                .with_layout(Layout::vertical())
+                .with_fill(fill)
+                .with_stroke(stroke)
+                .with_margin(Margin::same(margin)),
+            body
+        );
+        tooltip
+    });
+
+    // Place the tooltip below the mouse cursor, keeping it on screen
+    let tooltip_size = ui.memory().get::<LayoutInfo>(tooltip.id).rect.size();
+    let max_position = ui.window_size() - tooltip_size;
+    let position = (mouse_pos + vec2(0.0, 20.0)).min(max_position).max(Vec2::ZERO);
+    ui.set_transform(layer, TSTransform::translation(position));
+}
+
+/// Show a tooltip with some text once a node has been hovered for a moment
+pub fn text_tooltip<S: Into<String>>(ui: &mut UI, response: &Response, text: S) {
+    tooltip(ui, response, |ui| {
+        label(ui, text);
+    });
+}